@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS destiny_weapons (
+    id BIGINT PRIMARY KEY,
+    icon TEXT NOT NULL DEFAULT '',
+    name TEXT NOT NULL,
+    column_1 BIGINT[] NOT NULL DEFAULT '{}',
+    column_2 BIGINT[] NOT NULL DEFAULT '{}',
+    perk_1 BIGINT[] NOT NULL DEFAULT '{}',
+    perk_2 BIGINT[] NOT NULL DEFAULT '{}'
+);
+
+-- text_pattern_ops lets `lower(name) LIKE 'prefix%'` use the index.
+CREATE INDEX IF NOT EXISTS destiny_weapons_name_idx ON destiny_weapons (lower(name) text_pattern_ops);
+
+CREATE TABLE IF NOT EXISTS destiny_perks (
+    id BIGINT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT ''
+);
+
+CREATE INDEX IF NOT EXISTS destiny_perks_name_idx ON destiny_perks (lower(name));
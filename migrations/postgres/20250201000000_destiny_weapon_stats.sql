@@ -0,0 +1,8 @@
+-- Base stats of each item hash, NULL when the weapon does not have the stat.
+ALTER TABLE destiny_weapons ADD COLUMN impact BIGINT;
+ALTER TABLE destiny_weapons ADD COLUMN "range" BIGINT;
+ALTER TABLE destiny_weapons ADD COLUMN stability BIGINT;
+ALTER TABLE destiny_weapons ADD COLUMN handling BIGINT;
+ALTER TABLE destiny_weapons ADD COLUMN reload_speed BIGINT;
+ALTER TABLE destiny_weapons ADD COLUMN magazine BIGINT;
+ALTER TABLE destiny_weapons ADD COLUMN rounds_per_minute BIGINT;
@@ -0,0 +1,21 @@
+-- Plug columns hold JSON arrays of item hashes, e.g. '[1234, 5678]'.
+CREATE TABLE IF NOT EXISTS destiny_weapons (
+    id INTEGER PRIMARY KEY,
+    icon TEXT NOT NULL DEFAULT '',
+    name TEXT NOT NULL COLLATE NOCASE,
+    column_1 TEXT NOT NULL DEFAULT '[]',
+    column_2 TEXT NOT NULL DEFAULT '[]',
+    perk_1 TEXT NOT NULL DEFAULT '[]',
+    perk_2 TEXT NOT NULL DEFAULT '[]'
+);
+
+-- NOCASE lets `name LIKE 'prefix%'` use the index.
+CREATE INDEX IF NOT EXISTS destiny_weapons_name_idx ON destiny_weapons (name COLLATE NOCASE);
+
+CREATE TABLE IF NOT EXISTS destiny_perks (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL COLLATE NOCASE,
+    description TEXT NOT NULL DEFAULT ''
+);
+
+CREATE INDEX IF NOT EXISTS destiny_perks_name_idx ON destiny_perks (name COLLATE NOCASE);
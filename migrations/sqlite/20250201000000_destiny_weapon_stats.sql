@@ -0,0 +1,8 @@
+-- Base stats of each item hash, NULL when the weapon does not have the stat.
+ALTER TABLE destiny_weapons ADD COLUMN impact INTEGER;
+ALTER TABLE destiny_weapons ADD COLUMN "range" INTEGER;
+ALTER TABLE destiny_weapons ADD COLUMN stability INTEGER;
+ALTER TABLE destiny_weapons ADD COLUMN handling INTEGER;
+ALTER TABLE destiny_weapons ADD COLUMN reload_speed INTEGER;
+ALTER TABLE destiny_weapons ADD COLUMN magazine INTEGER;
+ALTER TABLE destiny_weapons ADD COLUMN rounds_per_minute INTEGER;
@@ -1,13 +1,17 @@
-use futures::{StreamExt, future, stream};
 use serenity::all::{
     CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand,
     CreateCommandOption, EditInteractionResponse, ResolvedOption, ResolvedValue,
 };
 use sqlx::{Database, Pool};
+use zayden_core::parse_options;
 
 use crate::{
-    DestinyPerkManager, DestinyWeaponManager,
-    endgame_analysis::{EndgameAnalysisSheet, Weapon},
+    DestinyPerkManager, DestinyWeaponManager, Error, Result,
+    endgame_analysis::{
+        Affinity, Ammo, Archetype, EndgameConfig, Frame, SnapshotCache, Strictness, TIERS,
+        TierLabel, Variant,
+    },
+    wishlist::{Wishlist, WishlistFormat, WishlistOptions},
 };
 
 pub struct DimWishlistCommand;
@@ -20,55 +24,167 @@ impl DimWishlistCommand {
     >(
         ctx: &Context,
         interaction: &CommandInteraction,
-        mut options: Vec<ResolvedOption<'_>>,
+        options: Vec<ResolvedOption<'_>>,
         pool: &Pool<Db>,
-    ) {
-        interaction.defer_ephemeral(ctx).await.unwrap();
+        config: &EndgameConfig,
+        snapshots: &SnapshotCache,
+    ) -> Result<()> {
+        let result = Self::execute::<Db, WeaponManager, PerkManager>(
+            ctx,
+            interaction,
+            options,
+            pool,
+            config,
+            snapshots,
+        )
+        .await;
 
-        let strict = match options.pop().map(|o| o.value) {
-            Some(ResolvedValue::String(strict)) => strict,
-            _ => "soft",
+        if let Err(e) = &result {
+            e.respond(ctx, interaction).await?;
+        }
+
+        result
+    }
+
+    async fn execute<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
+        ctx: &Context,
+        interaction: &CommandInteraction,
+        options: Vec<ResolvedOption<'_>>,
+        pool: &Pool<Db>,
+        config: &EndgameConfig,
+        snapshots: &SnapshotCache,
+    ) -> Result<()> {
+        interaction.defer_ephemeral(ctx).await?;
+
+        let options = parse_options(options);
+
+        let strictness = match options.get("strict") {
+            Some(ResolvedValue::String(strict)) => strict
+                .parse()
+                .map_err(|_| Error::Parse(format!("strictness '{}'", strict)))?,
+            _ => Strictness::Soft,
+        };
+
+        let min_tier = match options.get("tier") {
+            Some(ResolvedValue::String(tier)) => Some(
+                tier.parse::<TierLabel>()
+                    .map_err(|_| Error::Parse(format!("tier '{}'", tier)))?,
+            ),
+            _ => None,
+        };
+
+        let archetype = match options.get("archetype") {
+            Some(ResolvedValue::String(archetype)) => Some(
+                archetype
+                    .parse::<Archetype>()
+                    .map_err(|_| Error::Parse(format!("archetype '{}'", archetype)))?,
+            ),
+            _ => None,
+        };
+
+        let affinities = match options.get("affinity") {
+            Some(ResolvedValue::String(affinities)) => affinities
+                .split([',', '/'])
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(|a| {
+                    a.parse::<Affinity>()
+                        .map_err(|_| Error::Parse(format!("affinity '{}'", a)))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => Vec::new(),
+        };
+
+        let frame = match options.get("frame") {
+            Some(ResolvedValue::String(frame)) => Some(
+                frame
+                    .parse::<Frame>()
+                    .map_err(|_| Error::Parse(format!("frame '{}'", frame)))?,
+            ),
+            _ => None,
+        };
+
+        let ammo = match options.get("ammo") {
+            Some(ResolvedValue::String(ammo)) => Some(
+                ammo.parse::<Ammo>()
+                    .map_err(|_| Error::Parse(format!("ammo '{}'", ammo)))?,
+            ),
+            _ => None,
+        };
+
+        let trash = match options.get("trash") {
+            Some(ResolvedValue::String(tier)) => Some(
+                tier.parse::<TierLabel>()
+                    .map_err(|_| Error::Parse(format!("tier '{}'", tier)))?,
+            ),
+            _ => None,
         };
 
-        let tier = match strict {
-            "soft" => vec!["S", "A", "B", "C", "D", "E", "F", "G"],
-            "regular" => vec!["S", "A", "B", "C", "D"],
-            "semi-strict" => vec!["S", "A", "B", "C"],
-            "strict" => vec!["S", "A", "B"],
-            "very strict" => vec!["S", "A"],
-            "uber strict" => vec!["S"],
-            _ => unreachable!(),
+        let format = match options.get("format") {
+            Some(ResolvedValue::String(format)) => format
+                .parse()
+                .map_err(|_| Error::Parse(format!("format '{}'", format)))?,
+            _ => WishlistFormat::Dim,
         };
 
-        let weapons = match std::fs::read_to_string("weapons.json") {
-            Ok(weapons) => weapons,
-            Err(_) => {
-                EndgameAnalysisSheet::update::<Db, WeaponManager>(pool)
-                    .await
-                    .unwrap();
-                std::fs::read_to_string("weapons.json").unwrap()
-            }
+        let max_lines = match options.get("max_lines") {
+            Some(ResolvedValue::Integer(max_lines)) => Some(*max_lines as usize),
+            _ => None,
         };
-        let weapons: Vec<Weapon> = serde_json::from_str(&weapons).unwrap();
-
-        let wishlist = stream::iter(weapons)
-            .filter(|weapon| future::ready(tier.contains(&weapon.tier.tier().as_str())))
-            .then(|weapon| {
-                let pool = pool.clone();
-                async move {
-                    weapon
-                        .as_wishlist::<Db, WeaponManager, PerkManager>(&pool)
-                        .await
-                }
+
+        let exclude_variants = Variant::ALL
+            .into_iter()
+            .filter(|variant| {
+                matches!(
+                    options.get(variant.to_string().to_lowercase().as_str()),
+                    Some(ResolvedValue::Boolean(false))
+                )
             })
-            .collect::<Vec<_>>()
-            .await;
+            .collect();
+
+        let trash_rolls = matches!(
+            options.get("trash_rolls"),
+            Some(ResolvedValue::Boolean(true))
+        );
+
+        let loaded = snapshots
+            .load::<Db, WeaponManager, PerkManager>(pool, config)
+            .await?;
+
+        let options = match min_tier {
+            Some(min_tier) => WishlistOptions::new(min_tier),
+            None => WishlistOptions::from_strictness(strictness),
+        }
+        .trash(trash)
+        .trash_rolls(trash_rolls)
+        .max_lines(max_lines)
+        .exclude_variants(exclude_variants)
+        .archetype(archetype)
+        .affinities(affinities)
+        .frame(frame)
+        .ammo(ammo);
 
-        let wishlist = format!("title: DIM Wishlist\n\n{}", wishlist.join("\n\n"));
+        let estimate = Wishlist::estimate(&loaded.snapshot, &options);
+
+        interaction
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new()
+                    .content(format!("Generating wishlist (~{} lines)...", estimate)),
+            )
+            .await?;
+
+        let serializer = format.serializer();
+        let wishlist = serializer.serialize(&loaded.wishlists.generate(&loaded.snapshot, &options));
+        let label = options.label();
 
         let file = CreateAttachment::bytes(
             wishlist.as_bytes(),
-            format!("PVE Wishlist ({}).txt", strict),
+            format!("{}.{}", options.file_name(), serializer.extension()),
         );
 
         interaction
@@ -76,23 +192,104 @@ impl DimWishlistCommand {
                 ctx,
                 EditInteractionResponse::new()
                     .new_attachment(file)
-                    .content(format!("PVE Wishlist ({}):", strict)),
+                    .content(format!(
+                        "PVE Wishlist ({}, {}, {} lines):",
+                        label, format, estimate
+                    )),
             )
-            .await
-            .unwrap();
+            .await?;
+
+        Ok(())
     }
 
     pub fn register() -> CreateCommand {
-        CreateCommand::new("dimwishlist")
+        let variant_options = Variant::ALL
+            .into_iter()
+            .filter(|variant| *variant != Variant::Base)
+            .map(|variant| {
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    variant.to_string().to_lowercase(),
+                    format!("Include {} versions, defaults to true", variant),
+                )
+            });
+
+        let strict_option = Strictness::ALL.iter().fold(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "strict",
+                "Soft: S-F | Regular: S-D | Semi: S-C | Strict: S-B | Very: S-A | Uber: S",
+            ),
+            |option, strictness| option.add_string_choice(strictness.to_string(), strictness.id()),
+        );
+
+        let command = CreateCommand::new("dimwishlist")
             .description("Get a wishlist from DIM")
+            .add_option(strict_option)
+            .add_option(tier_option(
+                "tier",
+                "The lowest tier to include, overrides strict",
+            ))
             .add_option(
-                CreateCommandOption::new(CommandOptionType::String, "strict", "Soft: All | Regular: S, A, B, C, D | Semi: S, A, B, C | Strict: S, A, B | Very: S, A | Uber: S")
-                    .add_string_choice("Soft", "soft")
-                    .add_string_choice("Regular", "regular")
-                    .add_string_choice("Semi-strict", "semi-strict")
-                    .add_string_choice("Strict", "strict")
-                    .add_string_choice("Very Strict", "very strict")
-                    .add_string_choice("Uber Strict", "uber strict"),
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "format",
+                    "The wishlist format, defaults to DIM",
+                )
+                .add_string_choice("DIM", "dim")
+                .add_string_choice("Little Light", "littlelight")
+                .add_string_choice("JSON", "json"),
             )
+            .add_option(tier_option(
+                "trash",
+                "Mark weapons at or below this tier as trash",
+            ))
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "trash_rolls",
+                "Mark rolls without any recommended perks on B to D tier weapons as trash",
+            ))
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "max_lines",
+                    "The maximum number of lines per weapon, lowest priority rolls are dropped first",
+                )
+                .min_int_value(1),
+            )
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "archetype",
+                "Only include this archetype, e.g. Shotgun or HCs",
+            ))
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "affinity",
+                "Only include these affinities, e.g. Arc, Strand",
+            ))
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "frame",
+                "Only include this frame, e.g. Lightweight",
+            ))
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "ammo",
+                    "Only include this ammo type",
+                )
+                .add_string_choice("Primary", "Primary")
+                .add_string_choice("Special", "Special")
+                .add_string_choice("Heavy", "Heavy"),
+            );
+
+        variant_options.fold(command, |command, option| command.add_option(option))
     }
 }
+
+fn tier_option(name: &str, description: &str) -> CreateCommandOption {
+    TIERS.iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, name, description),
+        |option, tier| option.add_string_choice(tier.to_string(), tier.to_string()),
+    )
+}
@@ -0,0 +1,34 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy, Deserialize, Serialize)]
+pub enum Ammo {
+    #[default]
+    Primary,
+    Special,
+    Heavy,
+}
+
+impl FromStr for Ammo {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Primary" => Ok(Ammo::Primary),
+            "Special" => Ok(Ammo::Special),
+            "Heavy" => Ok(Ammo::Heavy),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Ammo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ammo::Primary => write!(f, "Primary"),
+            Ammo::Special => write!(f, "Special"),
+            Ammo::Heavy => write!(f, "Heavy"),
+        }
+    }
+}
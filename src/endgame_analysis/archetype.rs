@@ -0,0 +1,170 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::Ammo;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
+pub enum Archetype {
+    AutoRifle,
+    Bow,
+    HandCannon,
+    PulseRifle,
+    ScoutRifle,
+    Sidearm,
+    SubmachineGun,
+    BreechGrenadeLauncher,
+    FusionRifle,
+    Glaive,
+    Shotgun,
+    SniperRifle,
+    TraceRifle,
+    RocketSidearm,
+    HeavyGrenadeLauncher,
+    LinearFusionRifle,
+    MachineGun,
+    RocketLauncher,
+    Sword,
+}
+
+impl Archetype {
+    pub const ALL: [Archetype; 19] = [
+        Archetype::AutoRifle,
+        Archetype::Bow,
+        Archetype::HandCannon,
+        Archetype::PulseRifle,
+        Archetype::ScoutRifle,
+        Archetype::Sidearm,
+        Archetype::SubmachineGun,
+        Archetype::BreechGrenadeLauncher,
+        Archetype::FusionRifle,
+        Archetype::Glaive,
+        Archetype::Shotgun,
+        Archetype::SniperRifle,
+        Archetype::TraceRifle,
+        Archetype::RocketSidearm,
+        Archetype::HeavyGrenadeLauncher,
+        Archetype::LinearFusionRifle,
+        Archetype::MachineGun,
+        Archetype::RocketLauncher,
+        Archetype::Sword,
+    ];
+
+    /// Sheet tab titles, plurals and short names that refer to this archetype.
+    pub fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Archetype::AutoRifle => &["Autos", "Auto", "Auto Rifles"],
+            Archetype::Bow => &["Bows", "Combat Bow", "Combat Bows"],
+            Archetype::HandCannon => &["HCs", "HC", "Hand Cannons"],
+            Archetype::PulseRifle => &["Pulses", "Pulse", "Pulse Rifles"],
+            Archetype::ScoutRifle => &["Scouts", "Scout", "Scout Rifles"],
+            Archetype::Sidearm => &["Sidearms"],
+            Archetype::SubmachineGun => &["SMGs", "SMG", "Submachine Guns"],
+            Archetype::BreechGrenadeLauncher => &["BGLs", "BGL", "Breech Grenade Launchers"],
+            Archetype::FusionRifle => &["Fusions", "Fusion", "Fusion Rifles"],
+            Archetype::Glaive => &["Glaives"],
+            Archetype::Shotgun => &["Shotguns"],
+            Archetype::SniperRifle => &["Snipers", "Sniper", "Sniper Rifles"],
+            Archetype::TraceRifle => &["Traces", "Trace", "Trace Rifles"],
+            Archetype::RocketSidearm => &["Rocket Sidearms"],
+            Archetype::HeavyGrenadeLauncher => &["HGLs", "HGL", "Heavy Grenade Launchers"],
+            Archetype::LinearFusionRifle => &["LFRs", "LFR", "Linear Fusion", "Linear Fusions"],
+            Archetype::MachineGun => &["LMGs", "LMG", "Machine Guns"],
+            Archetype::RocketLauncher => &["Rockets", "Rocket", "Rocket Launchers"],
+            Archetype::Sword => &["Swords"],
+        }
+    }
+
+    pub fn ammo(self) -> Ammo {
+        match self {
+            Archetype::AutoRifle
+            | Archetype::Bow
+            | Archetype::HandCannon
+            | Archetype::PulseRifle
+            | Archetype::ScoutRifle
+            | Archetype::Sidearm
+            | Archetype::SubmachineGun => Ammo::Primary,
+            Archetype::BreechGrenadeLauncher
+            | Archetype::FusionRifle
+            | Archetype::Glaive
+            | Archetype::Shotgun
+            | Archetype::SniperRifle
+            | Archetype::TraceRifle
+            | Archetype::RocketSidearm => Ammo::Special,
+            Archetype::HeavyGrenadeLauncher
+            | Archetype::LinearFusionRifle
+            | Archetype::MachineGun
+            | Archetype::RocketLauncher
+            | Archetype::Sword => Ammo::Heavy,
+        }
+    }
+
+    /// The in-game weapon family, which groups breech and heavy grenade launchers together.
+    pub fn family(self) -> &'static str {
+        match self {
+            Archetype::BreechGrenadeLauncher | Archetype::HeavyGrenadeLauncher => {
+                "Grenade Launcher"
+            }
+            Archetype::AutoRifle => "Auto Rifle",
+            Archetype::Bow => "Combat Bow",
+            Archetype::HandCannon => "Hand Cannon",
+            Archetype::PulseRifle => "Pulse Rifle",
+            Archetype::ScoutRifle => "Scout Rifle",
+            Archetype::Sidearm | Archetype::RocketSidearm => "Sidearm",
+            Archetype::SubmachineGun => "Submachine Gun",
+            Archetype::FusionRifle => "Fusion Rifle",
+            Archetype::Glaive => "Glaive",
+            Archetype::Shotgun => "Shotgun",
+            Archetype::SniperRifle => "Sniper Rifle",
+            Archetype::TraceRifle => "Trace Rifle",
+            Archetype::LinearFusionRifle => "Linear Fusion Rifle",
+            Archetype::MachineGun => "Machine Gun",
+            Archetype::RocketLauncher => "Rocket Launcher",
+            Archetype::Sword => "Sword",
+        }
+    }
+
+    pub fn matches(self, s: &str) -> bool {
+        self.to_string().eq_ignore_ascii_case(s)
+            || self.aliases().iter().any(|a| a.eq_ignore_ascii_case(s))
+    }
+}
+
+impl FromStr for Archetype {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        Self::ALL
+            .into_iter()
+            .find(|archetype| archetype.matches(s))
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Archetype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Archetype::AutoRifle => write!(f, "Auto Rifle"),
+            Archetype::Bow => write!(f, "Bow"),
+            Archetype::HandCannon => write!(f, "Hand Cannon"),
+            Archetype::PulseRifle => write!(f, "Pulse Rifle"),
+            Archetype::ScoutRifle => write!(f, "Scout Rifle"),
+            Archetype::Sidearm => write!(f, "Sidearm"),
+            Archetype::SubmachineGun => write!(f, "Submachine Gun"),
+            Archetype::BreechGrenadeLauncher => write!(f, "Breech Grenade Launcher"),
+            Archetype::FusionRifle => write!(f, "Fusion Rifle"),
+            Archetype::Glaive => write!(f, "Glaive"),
+            Archetype::Shotgun => write!(f, "Shotgun"),
+            Archetype::SniperRifle => write!(f, "Sniper Rifle"),
+            Archetype::TraceRifle => write!(f, "Trace Rifle"),
+            Archetype::RocketSidearm => write!(f, "Rocket Sidearm"),
+            Archetype::HeavyGrenadeLauncher => write!(f, "Heavy Grenade Launcher"),
+            Archetype::LinearFusionRifle => write!(f, "Linear Fusion Rifle"),
+            Archetype::MachineGun => write!(f, "Machine Gun"),
+            Archetype::RocketLauncher => write!(f, "Rocket Launcher"),
+            Archetype::Sword => write!(f, "Sword"),
+        }
+    }
+}
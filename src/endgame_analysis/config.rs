@@ -0,0 +1,165 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use google_sheets_api::types::common::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+use super::Ammo;
+use super::fetch::{RetryPolicy, SHEETS_API_URL};
+use super::snapshot::SNAPSHOT_PATH;
+use super::tier::google_colour_to_rgb;
+
+pub const ENDGAME_ANALYSIS_ID: &str = "1JM-0SlxVDAi-C6rGVlLxa-J1WGewEeL8Qvq4htWZHhY";
+
+/// Maps a tab colour to the ammo type of the weapons listed on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TabColour {
+    /// 0xRRGGBB
+    pub colour: u32,
+    pub ammo: Ammo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EndgameConfig {
+    pub spreadsheet_id: String,
+    /// Base URL of the Sheets API, replaced by a local server in tests.
+    pub api_url: String,
+    /// Falls back to `GOOGLE_API_KEY` when unset.
+    pub api_key: Option<String>,
+    /// Tab titles to parse. Every visible tab is parsed when empty.
+    pub include_tabs: Vec<String>,
+    pub exclude_tabs: Vec<String>,
+    pub tab_colours: Vec<TabColour>,
+    /// The largest difference allowed per colour channel, out of 255.
+    pub colour_tolerance: u8,
+    pub snapshot_path: PathBuf,
+    pub retry: RetryPolicy,
+}
+
+impl Default for EndgameConfig {
+    fn default() -> Self {
+        Self {
+            spreadsheet_id: String::from(ENDGAME_ANALYSIS_ID),
+            api_url: String::from(SHEETS_API_URL),
+            api_key: None,
+            include_tabs: Vec::new(),
+            exclude_tabs: Vec::new(),
+            tab_colours: vec![
+                TabColour {
+                    colour: 0xF3F3F3,
+                    ammo: Ammo::Primary,
+                },
+                TabColour {
+                    colour: 0x00FF00,
+                    ammo: Ammo::Special,
+                },
+                TabColour {
+                    colour: 0x9900FF,
+                    ammo: Ammo::Heavy,
+                },
+            ],
+            colour_tolerance: 2,
+            snapshot_path: PathBuf::from(SNAPSHOT_PATH),
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl EndgameConfig {
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|e| Error::Config(e.to_string()))
+    }
+
+    /// Reads the TOML file at `path`, then applies the environment overrides.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let toml = fs::read_to_string(path)?;
+
+        toml::from_str::<Self>(&toml)
+            .map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))?
+            .with_env_overrides()
+    }
+
+    /// Reads the TOML file at `ENDGAME_ANALYSIS_CONFIG`, if set, then applies the
+    /// environment overrides.
+    pub fn from_env() -> Result<Self> {
+        match env::var("ENDGAME_ANALYSIS_CONFIG") {
+            Ok(path) => Self::load(path),
+            Err(_) => Self::default().with_env_overrides(),
+        }
+    }
+
+    /// Applies `ENDGAME_ANALYSIS_ID`, `GOOGLE_API_KEY`, `ENDGAME_ANALYSIS_INCLUDE_TABS`,
+    /// `ENDGAME_ANALYSIS_EXCLUDE_TABS`, `ENDGAME_ANALYSIS_COLOUR_TOLERANCE` and
+    /// `ENDGAME_ANALYSIS_SNAPSHOT` on top of `self`.
+    fn with_env_overrides(self) -> Result<Self> {
+        let mut config = self;
+
+        if let Ok(id) = env::var("ENDGAME_ANALYSIS_ID") {
+            config.spreadsheet_id = id;
+        }
+        if let Ok(key) = env::var("GOOGLE_API_KEY") {
+            config.api_key = Some(key);
+        }
+        if let Ok(tabs) = env::var("ENDGAME_ANALYSIS_INCLUDE_TABS") {
+            config.include_tabs = split_list(&tabs);
+        }
+        if let Ok(tabs) = env::var("ENDGAME_ANALYSIS_EXCLUDE_TABS") {
+            config.exclude_tabs = split_list(&tabs);
+        }
+        if let Ok(tolerance) = env::var("ENDGAME_ANALYSIS_COLOUR_TOLERANCE") {
+            config.colour_tolerance = tolerance
+                .parse()
+                .map_err(|_| Error::Config(format!("invalid colour tolerance '{}'", tolerance)))?;
+        }
+        if let Ok(path) = env::var("ENDGAME_ANALYSIS_SNAPSHOT") {
+            config.snapshot_path = PathBuf::from(path);
+        }
+
+        Ok(config)
+    }
+
+    pub fn api_key(&self) -> Result<&str> {
+        self.api_key
+            .as_deref()
+            .ok_or_else(|| Error::Config(String::from("GOOGLE_API_KEY is not set")))
+    }
+
+    pub fn includes_tab(&self, title: &str) -> bool {
+        let listed = |tabs: &[String]| {
+            tabs.iter()
+                .any(|t| t.trim().eq_ignore_ascii_case(title.trim()))
+        };
+
+        (self.include_tabs.is_empty() || listed(&self.include_tabs)) && !listed(&self.exclude_tabs)
+    }
+
+    /// The ammo type of the first tab colour within the tolerance.
+    pub fn ammo(&self, colour: &Color) -> Option<Ammo> {
+        let rgb = google_colour_to_rgb(colour);
+        let tolerance = u32::from(self.colour_tolerance);
+
+        self.tab_colours
+            .iter()
+            .find(|tab| {
+                [16, 8, 0].into_iter().all(|shift| {
+                    let a = (rgb >> shift) & 0xFF;
+                    let b = (tab.colour >> shift) & 0xFF;
+                    a.abs_diff(b) <= tolerance
+                })
+            })
+            .map(|tab| tab.ammo)
+    }
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use google_sheets_api::types::sheet::Sheet;
+use reqwest::header::RETRY_AFTER;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SheetsError;
+use crate::{Error, Result};
+
+use super::{EndgameConfig, SheetsDump};
+
+pub const SHEETS_API_URL: &str = "https://sheets.googleapis.com";
+
+/// Bounds on how long and how often a spreadsheet fetch is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    /// The delay before the second attempt, doubled for every attempt after it.
+    pub base_delay_ms: u64,
+    /// Caps the backoff. A longer `Retry-After` is still honoured, bounded by `timeout_ms`.
+    pub max_delay_ms: u64,
+    pub attempt_timeout_ms: u64,
+    /// Covers every attempt and the delays between them.
+    pub timeout_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay_ms: 1_000,
+            max_delay_ms: 30_000,
+            attempt_timeout_ms: 60_000,
+            timeout_ms: 180_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Exponential backoff with jitter, waiting at least as long as `Retry-After` asks.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let max = Duration::from_millis(self.max_delay_ms);
+        let backoff = Duration::from_millis(self.base_delay_ms)
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(max);
+
+        // Anywhere between half and the full backoff.
+        let jittered = backoff / 2 + backoff.mul_f64(fastrand::f64() / 2.0);
+
+        retry_after.map_or(jittered, |r| r.max(jittered))
+    }
+}
+
+/// Parses `Retry-After` as either delay seconds or an HTTP-date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+pub struct SheetsFetcher {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: String,
+    policy: RetryPolicy,
+}
+
+impl SheetsFetcher {
+    pub fn new(config: &EndgameConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.retry.attempt_timeout_ms))
+            .build()
+            .map_err(|e| Error::Config(format!("HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            api_url: config.api_url.trim_end_matches('/').to_string(),
+            api_key: config.api_key()?.to_string(),
+            policy: config.retry,
+        })
+    }
+
+    /// Fetches every tab with grid data, retrying transient failures.
+    pub async fn fetch(&self, spreadsheet_id: &str) -> Result<Vec<Sheet>> {
+        let attempts = async {
+            let mut attempt = 1;
+
+            loop {
+                match self.fetch_once(spreadsheet_id).await {
+                    Ok(sheets) => return Ok(sheets),
+                    Err(e) if e.is_retryable() && attempt < self.policy.max_attempts => {
+                        let delay = self.policy.delay(attempt, e.retry_after());
+                        eprintln!(
+                            "Sheets fetch attempt {} failed ({}), retrying in {:?}",
+                            attempt, e, delay
+                        );
+
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        };
+
+        let sheets = tokio::time::timeout(Duration::from_millis(self.policy.timeout_ms), attempts)
+            .await
+            .map_err(|_| SheetsError::Timeout)??;
+
+        Ok(sheets)
+    }
+
+    async fn fetch_once(
+        &self,
+        spreadsheet_id: &str,
+    ) -> std::result::Result<Vec<Sheet>, SheetsError> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/v4/spreadsheets/{}",
+                self.api_url, spreadsheet_id
+            ))
+            .query(&[("includeGridData", "true"), ("key", self.api_key.as_str())])
+            .send()
+            .await
+            .map_err(|e| SheetsError::Network(e.without_url().to_string()))?;
+
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| parse_retry_after(v, Utc::now()));
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SheetsError::Network(e.without_url().to_string()))?;
+
+        if !status.is_success() {
+            return Err(SheetsError::Status {
+                status: status.as_u16(),
+                message: error_message(&body),
+                retry_after,
+            });
+        }
+
+        serde_json::from_str::<SheetsDump>(&body)
+            .map(|dump| dump.sheets)
+            .map_err(|e| SheetsError::Decode(e.to_string()))
+    }
+}
+
+/// The `error.message` of a Google API error body, or the body itself.
+fn error_message(body: &str) -> String {
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        error: ErrorMessage,
+    }
+
+    #[derive(Deserialize)]
+    struct ErrorMessage {
+        message: String,
+    }
+
+    serde_json::from_str::<ErrorBody>(body)
+        .map(|b| b.error.message)
+        .unwrap_or_else(|_| body.trim().to_string())
+}
@@ -1,100 +1,196 @@
-use std::env;
+use std::fs;
+use std::path::Path;
 
-use futures::{StreamExt, future, stream};
-use google_sheets_api::SheetsClientBuilder;
-use google_sheets_api::types::common::Color;
-use google_sheets_api::types::sheet::GridData;
+use futures::{StreamExt, stream};
+use google_sheets_api::types::sheet::{GridData, Sheet};
+use serde::Deserialize;
 use sqlx::{Database, Pool};
 
 pub mod affinity;
+pub mod ammo;
+pub mod archetype;
+pub mod config;
+pub mod fetch;
 pub mod frame;
+pub mod perk;
+pub mod query;
+pub mod report;
+pub mod snapshot;
+pub mod stats;
 pub mod tier;
+pub mod variant;
 pub mod weapon;
 
 pub use affinity::Affinity;
+pub use ammo::Ammo;
+pub use archetype::Archetype;
+pub use config::{EndgameConfig, TabColour};
+pub use fetch::{RetryPolicy, SheetsFetcher};
 pub use frame::Frame;
+pub use perk::Perk;
+pub use query::{SortKey, WeaponQuery};
+pub use report::{RefreshIssue, RefreshReport};
+pub use snapshot::{LoadedSnapshot, Snapshot, SnapshotCache, SnapshotDiff};
+pub use stats::{Stat, WeaponStats};
 pub use tier::Tier;
-pub use tier::{TIERS, TierLabel};
+pub use tier::{Strictness, TIERS, TierLabel};
+pub use variant::Variant;
 pub use weapon::{Weapon, WeaponBuilder};
 
-use crate::{DestinyWeaponManager, Result};
+use crate::{DestinyPerkManager, DestinyWeaponManager, Error, Result};
 
-const ENDGAME_ANALYSIS_ID: &str = "1JM-0SlxVDAi-C6rGVlLxa-J1WGewEeL8Qvq4htWZHhY";
+const CONCURRENT_REQUESTS: usize = 16;
 
-fn primary_colour(color: &Color) -> bool {
-    color.red == 0.9529412 && color.green == 0.9529412 && color.blue == 0.9529412
-}
-
-fn special_colour(color: &Color) -> bool {
-    color.red == 0.0 && color.green == 1.0 && color.blue == 0.0
-}
-
-fn heavy_colour(color: &Color) -> bool {
-    color.red == 0.6 && color.green == 0.0 && color.blue == 1.0
+#[derive(Deserialize)]
+struct SheetsDump {
+    sheets: Vec<Sheet>,
 }
 
 pub struct EndgameAnalysisSheet;
 
 impl EndgameAnalysisSheet {
-    pub async fn update<Db: Database, Manager: DestinyWeaponManager<Db>>(
+    pub async fn update<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
         pool: &Pool<Db>,
-    ) -> Result<()> {
-        let api_key = env::var("GOOGLE_API_KEY").unwrap();
-
-        let client = SheetsClientBuilder::new(api_key).build().unwrap();
-
-        let spreadsheet = client.spreadsheet(ENDGAME_ANALYSIS_ID, true).await.unwrap();
+        config: &EndgameConfig,
+    ) -> Result<RefreshReport> {
+        let sheets = SheetsFetcher::new(config)?
+            .fetch(&config.spreadsheet_id)
+            .await?;
 
-        let iter = spreadsheet
-            .sheets
-            .into_iter()
-            .filter(|s| !s.properties.hidden)
-            .filter(|s| {
-                primary_colour(&s.properties.tab_color)
-                    || special_colour(&s.properties.tab_color)
-                    || heavy_colour(&s.properties.tab_color)
-            })
-            .map(|mut sheet| (sheet.properties.title, sheet.data.pop().unwrap()));
+        Self::update_from_sheets::<Db, WeaponManager, PerkManager>(pool, config, sheets).await
+    }
 
-        let weapons = stream::iter(iter)
-            .then(|(name, data)| async {
-                Self::parse_weapon_data::<Db, Manager>(pool, name, data).await
-            })
-            .flat_map(stream::iter)
-            .collect::<Vec<_>>()
-            .await;
+    /// Rebuilds the snapshot from a saved Sheets API response, fetched with grid data included.
+    pub async fn update_from_dump<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
+        pool: &Pool<Db>,
+        config: &EndgameConfig,
+        path: impl AsRef<Path>,
+    ) -> Result<RefreshReport> {
+        let json = fs::read_to_string(path)?;
+        let dump = serde_json::from_str::<SheetsDump>(&json)?;
 
-        let json = serde_json::to_string(&weapons).unwrap();
-        std::fs::write("weapons.json", json).unwrap();
+        Self::update_from_sheets::<Db, WeaponManager, PerkManager>(pool, config, dump.sheets).await
+    }
 
-        Ok(())
+    pub async fn update_from_sheets<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
+        pool: &Pool<Db>,
+        config: &EndgameConfig,
+        sheets: Vec<Sheet>,
+    ) -> Result<RefreshReport> {
+        let mut tabs = Vec::new();
+        for mut sheet in sheets
+            .into_iter()
+            .filter(|s| !s.properties.hidden && config.includes_tab(&s.properties.title))
+        {
+            let Some(ammo) = config.ammo(&sheet.properties.tab_color) else {
+                continue;
+            };
+
+            let Ok(archetype) = sheet.properties.title.parse::<Archetype>() else {
+                eprintln!("Unknown archetype {}", sheet.properties.title);
+                continue;
+            };
+
+            let data = sheet.data.pop().ok_or_else(|| {
+                Error::Parse(format!("tab '{}': no grid data", sheet.properties.title))
+            })?;
+
+            tabs.push((archetype, ammo, data));
+        }
+
+        let mut report = RefreshReport::default();
+        let mut weapons = Vec::new();
+        for (archetype, ammo, data) in tabs {
+            weapons.extend(
+                Self::parse_weapon_data::<Db, WeaponManager, PerkManager>(
+                    pool,
+                    archetype,
+                    ammo,
+                    data,
+                    &mut report,
+                )
+                .await?,
+            );
+        }
+
+        report.weapons = weapons.len();
+        Snapshot::new(weapons).write(config)?;
+
+        Ok(report)
     }
 
-    async fn parse_weapon_data<Db: Database, Manager: DestinyWeaponManager<Db>>(
+    async fn parse_weapon_data<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
         pool: &Pool<Db>,
-        name: impl Into<String>,
+        archetype: Archetype,
+        ammo: Ammo,
         data: GridData,
-    ) -> Vec<Weapon> {
-        let name = name.into();
-
-        let mut iter = data.row_data.into_iter().skip(1);
-        let header = iter.next().unwrap();
-        let iter = iter.filter_map(|r| WeaponBuilder::from_row_data(&name, &header, r));
-
-        stream::iter(iter)
-            .then(|builder| async {
+        report: &mut RefreshReport,
+    ) -> Result<Vec<Weapon>> {
+        let mut iter = data.row_data.into_iter().enumerate().skip(1);
+        let (_, header) = iter
+            .next()
+            .ok_or_else(|| Error::Parse(format!("tab '{}': no header row", archetype)))?;
+
+        let mut builders = Vec::new();
+        for (i, row) in iter {
+            // A malformed row only drops that weapon. Sheet rows are numbered from 1.
+            match WeaponBuilder::from_row_data(archetype, &header, row) {
+                Ok(Some(builder)) => builders.push(builder.ammo(ammo)),
+                Ok(None) => {}
+                Err(Error::Parse(e)) => report.issues.push((
+                    format!("tab '{}', row {}", archetype, i + 1),
+                    RefreshIssue::Malformed(e),
+                )),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let results = stream::iter(builders)
+            .map(|builder| async move {
                 let name = builder.name.clone();
 
-                match builder.build::<Db, Manager>(pool).await {
-                    Ok(weapon) => Some(weapon),
-                    Err(_) => {
-                        eprintln!("Missing weapon {}", name);
-                        None
-                    }
-                }
+                let (weapon, issues) =
+                    match builder.build::<Db, WeaponManager, PerkManager>(pool).await {
+                        Ok((weapon, issues)) => (Some(weapon), issues),
+                        Err(Error::Database(sqlx::Error::RowNotFound)) => {
+                            (None, vec![RefreshIssue::Missing])
+                        }
+                        Err(Error::Parse(e)) => (None, vec![RefreshIssue::Malformed(e)]),
+                        Err(e) => return Err(e),
+                    };
+
+                Ok((name, weapon, issues))
             })
-            .filter_map(future::ready)
-            .collect()
-            .await
+            .buffered(CONCURRENT_REQUESTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut weapons = Vec::new();
+        for result in results {
+            let (name, weapon, issues) = result?;
+
+            report
+                .issues
+                .extend(issues.into_iter().map(|issue| (name.clone(), issue)));
+            weapons.extend(weapon);
+        }
+
+        Ok(weapons)
     }
 }
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Database, Pool};
+
+use crate::DestinyPerkManager;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Perk {
+    pub name: String,
+    /// Position in the sheet column, 0 being the most recommended.
+    pub priority: usize,
+    pub hashes: Vec<u32>,
+    /// Set when more than one rollable plug shares the name, i.e. an enhanced version exists.
+    pub enhanced: bool,
+    pub description: String,
+}
+
+impl Perk {
+    /// Resolves a newline separated sheet column to perks in priority order.
+    pub async fn resolve_column<Db: Database, Manager: DestinyPerkManager<Db>>(
+        pool: &Pool<Db>,
+        column: &str,
+    ) -> sqlx::Result<Vec<Perk>> {
+        let names = column
+            .split('\n')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        let records = Manager::get_all(pool, &names).await?;
+
+        let perks = names
+            .into_iter()
+            .enumerate()
+            .map(|(priority, name)| {
+                let records = records
+                    .iter()
+                    .filter(|perk| perk.name.to_lowercase() == name.to_lowercase())
+                    .collect::<Vec<_>>();
+
+                Perk {
+                    priority,
+                    hashes: records.iter().map(|perk| perk.id as u32).collect(),
+                    enhanced: false,
+                    description: records
+                        .first()
+                        .map(|perk| perk.description.clone())
+                        .unwrap_or_default(),
+                    name,
+                }
+            })
+            .collect();
+
+        Ok(perks)
+    }
+
+    /// Flags the perk as enhanceable if several of its hashes can roll in any of the pools.
+    pub fn detect_enhanced(&mut self, pools: &[&Vec<i64>]) {
+        let rollable = self
+            .hashes
+            .iter()
+            .filter(|hash| pools.iter().any(|pool| pool.contains(&(**hash as i64))))
+            .count();
+
+        self.enhanced = rollable > 1;
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        !self.hashes.is_empty()
+    }
+}
+
+impl fmt::Display for Perk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
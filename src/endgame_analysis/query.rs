@@ -0,0 +1,196 @@
+use std::cmp::Ordering;
+
+use super::{Affinity, Ammo, Archetype, Frame, Stat, TierLabel, Weapon};
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum SortKey {
+    /// The order the weapons appear in the sheet.
+    #[default]
+    Sheet,
+    Tier,
+    Name,
+    Archetype,
+    Reserves,
+    /// Tier first, then the stat within each tier.
+    Stat(Stat),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct WeaponQuery {
+    pub name: Option<String>,
+    pub search: Option<String>,
+    pub archetype: Option<Archetype>,
+    pub best_tier: Option<TierLabel>,
+    pub worst_tier: Option<TierLabel>,
+    pub affinities: Vec<Affinity>,
+    pub frame: Option<Frame>,
+    pub ammo: Option<Ammo>,
+    pub enhanceable: Option<bool>,
+    pub perk: Option<String>,
+    pub origin_trait: Option<String>,
+    pub min_reserves: Option<u16>,
+    pub max_reserves: Option<u16>,
+    pub sort: SortKey,
+    pub limit: Option<usize>,
+}
+
+impl WeaponQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches the weapon name exactly, ignoring case.
+    pub fn name(mut self, name: Option<impl Into<String>>) -> Self {
+        self.name = name.map(|n| n.into().to_lowercase());
+        self
+    }
+
+    /// Matches any weapon whose name contains the text, ignoring case.
+    pub fn search(mut self, search: Option<impl Into<String>>) -> Self {
+        self.search = search.map(|s| s.into().to_lowercase());
+        self
+    }
+
+    pub fn archetype(mut self, archetype: Option<Archetype>) -> Self {
+        self.archetype = archetype;
+        self
+    }
+
+    /// Keeps weapons ranked `min_tier` or better.
+    pub fn min_tier(mut self, min_tier: Option<TierLabel>) -> Self {
+        self.worst_tier = min_tier;
+        self
+    }
+
+    /// Keeps weapons ranked between `best` and `worst`, inclusive.
+    pub fn tier_range(mut self, best: TierLabel, worst: TierLabel) -> Self {
+        self.best_tier = Some(best);
+        self.worst_tier = Some(worst);
+        self
+    }
+
+    pub fn affinities(mut self, affinities: Vec<Affinity>) -> Self {
+        self.affinities = affinities;
+        self
+    }
+
+    pub fn frame(mut self, frame: Option<Frame>) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    pub fn ammo(mut self, ammo: Option<Ammo>) -> Self {
+        self.ammo = ammo;
+        self
+    }
+
+    pub fn enhanceable(mut self, enhanceable: Option<bool>) -> Self {
+        self.enhanceable = enhanceable;
+        self
+    }
+
+    /// Keeps weapons recommending the perk in either column, ignoring case.
+    pub fn perk(mut self, perk: Option<impl Into<String>>) -> Self {
+        self.perk = perk.map(|p| p.into().to_lowercase());
+        self
+    }
+
+    pub fn origin_trait(mut self, origin_trait: Option<impl Into<String>>) -> Self {
+        self.origin_trait = origin_trait.map(|o| o.into().to_lowercase());
+        self
+    }
+
+    pub fn reserves(mut self, min: Option<u16>, max: Option<u16>) -> Self {
+        self.min_reserves = min;
+        self.max_reserves = max;
+        self
+    }
+
+    pub fn sort(mut self, sort: SortKey) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn matches(&self, weapon: &Weapon) -> bool {
+        let tier = weapon.tier.tier;
+
+        self.name
+            .as_ref()
+            .is_none_or(|name| weapon.name().to_lowercase() == *name)
+            && self
+                .search
+                .as_ref()
+                .is_none_or(|search| weapon.name().to_lowercase().contains(search))
+            && self
+                .archetype
+                .is_none_or(|archetype| weapon.archetype() == archetype)
+            && self
+                .best_tier
+                .is_none_or(|best| tier != TierLabel::None && tier >= best)
+            && self.worst_tier.is_none_or(|worst| tier.is_at_least(worst))
+            && (self.affinities.is_empty() || self.affinities.contains(&weapon.affinity))
+            && self.frame.is_none_or(|frame| weapon.frame == Some(frame))
+            && self.ammo.is_none_or(|ammo| weapon.ammo == ammo)
+            && self
+                .enhanceable
+                .is_none_or(|enhanceable| weapon.enhanceable == enhanceable)
+            && self.perk.as_ref().is_none_or(|perk| {
+                weapon
+                    .perks()
+                    .iter()
+                    .flatten()
+                    .any(|p| p.name.to_lowercase() == *perk)
+            })
+            && self
+                .origin_trait
+                .as_ref()
+                .is_none_or(|origin| weapon.origin_trait().to_lowercase() == *origin)
+            && self
+                .min_reserves
+                .is_none_or(|min| weapon.reserves.is_some_and(|r| r >= min))
+            && self
+                .max_reserves
+                .is_none_or(|max| weapon.reserves.is_some_and(|r| r <= max))
+    }
+
+    pub fn run<'a>(&self, weapons: &'a [Weapon]) -> Vec<&'a Weapon> {
+        let mut weapons = weapons
+            .iter()
+            .filter(|weapon| self.matches(weapon))
+            .collect::<Vec<_>>();
+
+        weapons.sort_by(|a, b| self.compare(a, b));
+        weapons.truncate(self.limit.unwrap_or(usize::MAX));
+
+        weapons
+    }
+
+    fn compare(&self, a: &Weapon, b: &Weapon) -> Ordering {
+        let by_tier =
+            |a: &Weapon, b: &Weapon| a.tier.tier.cmp(&b.tier.tier).then(a.rank.cmp(&b.rank));
+
+        match self.sort {
+            SortKey::Sheet => Ordering::Equal,
+            SortKey::Tier => by_tier(a, b),
+            SortKey::Name => a.name().cmp(b.name()),
+            SortKey::Archetype => a
+                .archetype()
+                .cmp(&b.archetype())
+                .then_with(|| by_tier(a, b)),
+            // Deepest reserves first, unknown reserves last.
+            SortKey::Reserves => b.reserves.cmp(&a.reserves).then_with(|| by_tier(a, b)),
+            // Highest value first within a tier, weapons without the stat last.
+            SortKey::Stat(stat) => a
+                .tier
+                .tier
+                .cmp(&b.tier.tier)
+                .then_with(|| b.stats.get(stat).cmp(&a.stats.get(stat)))
+                .then(a.rank.cmp(&b.rank)),
+        }
+    }
+}
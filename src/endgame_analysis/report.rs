@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// What a refresh wrote, and the weapons it skipped or could only partly resolve.
+#[derive(Debug, Default)]
+pub struct RefreshReport {
+    pub weapons: usize,
+    /// Each issue with the weapon name, or the tab and row when the row has no usable name.
+    pub issues: Vec<(String, RefreshIssue)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefreshIssue {
+    /// The row could not be read and was skipped, e.g. a blank or "TBD" rank.
+    Malformed(String),
+    /// No manifest item has the weapon's name, so it was skipped.
+    Missing,
+    /// Sheet perk names without any perk hash.
+    UnresolvedPerks(Vec<String>),
+    /// Recommended perks that none of the weapon's items can roll.
+    PerksNotInPool(Vec<String>),
+    /// An item hash left out because it cannot roll any recommended combination.
+    NoPerkCombination(u32),
+}
+
+impl fmt::Display for RefreshIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RefreshIssue::Malformed(e) => write!(f, "skipped, {}", e),
+            RefreshIssue::Missing => write!(f, "skipped, not in the manifest"),
+            RefreshIssue::UnresolvedPerks(perks) => {
+                write!(f, "unresolved perks {}", perks.join(", "))
+            }
+            RefreshIssue::PerksNotInPool(perks) => {
+                write!(f, "perks {} are not in any perk pool", perks.join(", "))
+            }
+            RefreshIssue::NoPerkCombination(hash) => {
+                write!(f, "item {} dropped, no recommended perk combination", hash)
+            }
+        }
+    }
+}
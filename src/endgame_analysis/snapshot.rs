@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+use std::{fs, io};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Database, Pool};
+
+use crate::wishlist::WishlistCache;
+use crate::{DestinyPerkManager, DestinyWeaponManager, Error, Result};
+
+use super::{EndgameAnalysisSheet, EndgameConfig, Perk, Weapon};
+
+pub const SNAPSHOT_PATH: &str = "weapons.json";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub updated: DateTime<Utc>,
+    pub weapons: Vec<Weapon>,
+}
+
+impl Snapshot {
+    pub fn new(weapons: Vec<Weapon>) -> Self {
+        Self {
+            updated: Utc::now(),
+            weapons,
+        }
+    }
+
+    pub async fn load<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
+        pool: &Pool<Db>,
+        config: &EndgameConfig,
+    ) -> Result<Self> {
+        match Self::read(config) {
+            Ok(snapshot) => return Ok(snapshot),
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => eprintln!(
+                "Rebuilding unreadable snapshot {}: {}",
+                config.snapshot_path.display(),
+                e
+            ),
+        }
+
+        let report =
+            EndgameAnalysisSheet::update::<Db, WeaponManager, PerkManager>(pool, config).await?;
+        for (weapon, issue) in report.issues {
+            eprintln!("{}: {}", weapon, issue);
+        }
+
+        Self::read(config)
+    }
+
+    /// Reads the snapshot at [`EndgameConfig::snapshot_path`].
+    pub fn read(config: &EndgameConfig) -> Result<Self> {
+        Self::read_from(&config.snapshot_path)
+    }
+
+    /// Also reads snapshots written before perk columns were resolved, see [`migrate`].
+    pub fn read_from(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)?;
+
+        let mut value = serde_json::from_str::<Value>(&json)?;
+
+        // The first snapshots were a bare weapon list, without ammo types or item hashes.
+        if value.is_array() {
+            return Err(Error::Parse(format!(
+                "snapshot {}: the format predates versioned snapshots, run refresh to rebuild it",
+                path.display()
+            )));
+        }
+
+        migrate(&mut value);
+        let snapshot = serde_json::from_value(value)?;
+
+        Ok(snapshot)
+    }
+
+    pub fn write(&self, config: &EndgameConfig) -> Result<()> {
+        let json = serde_json::to_string(self)?;
+        fs::write(&config.snapshot_path, json)?;
+
+        Ok(())
+    }
+
+    pub fn version(&self) -> i64 {
+        self.updated.timestamp()
+    }
+
+    pub fn date(&self) -> String {
+        self.updated.format("%Y-%m-%d").to_string()
+    }
+
+    /// Compares weapons by name and archetype, treating tier, rank or perk changes as a change.
+    pub fn diff<'a>(&'a self, new: &'a Snapshot) -> SnapshotDiff<'a> {
+        let key = |w: &Weapon| (w.name().to_lowercase(), w.archetype());
+
+        let old_weapons = self
+            .weapons
+            .iter()
+            .map(|w| (key(w), w))
+            .collect::<HashMap<_, _>>();
+        let new_weapons = new
+            .weapons
+            .iter()
+            .map(|w| (key(w), w))
+            .collect::<HashMap<_, _>>();
+
+        let removed = self
+            .weapons
+            .iter()
+            .filter(|w| !new_weapons.contains_key(&key(w)))
+            .collect();
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+
+        for weapon in &new.weapons {
+            match old_weapons.get(&key(weapon)) {
+                None => added.push(weapon),
+                Some(old)
+                    if old.tier.tier != weapon.tier.tier
+                        || old.rank != weapon.rank
+                        || old.perks() != weapon.perks() =>
+                {
+                    changed.push((*old, weapon))
+                }
+                Some(_) => {}
+            }
+        }
+
+        SnapshotDiff {
+            added,
+            removed,
+            changed,
+        }
+    }
+}
+
+/// The snapshot file as last read, with the wishlists generated from it.
+pub struct LoadedSnapshot {
+    pub modified: SystemTime,
+    pub snapshot: Snapshot,
+    pub wishlists: WishlistCache,
+}
+
+/// Shares the last read snapshot between commands until the file's modified time changes.
+#[derive(Default)]
+pub struct SnapshotCache {
+    loaded: RwLock<Option<Arc<LoadedSnapshot>>>,
+}
+
+impl SnapshotCache {
+    /// Like [`Snapshot::load`], but only reads the file again once it has changed.
+    pub async fn load<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
+        &self,
+        pool: &Pool<Db>,
+        config: &EndgameConfig,
+    ) -> Result<Arc<LoadedSnapshot>> {
+        let modified = || fs::metadata(&config.snapshot_path).and_then(|m| m.modified());
+
+        if let Some(loaded) = modified().ok().and_then(|modified| self.get(modified)) {
+            return Ok(loaded);
+        }
+
+        let snapshot = Snapshot::load::<Db, WeaponManager, PerkManager>(pool, config).await?;
+
+        Ok(self.insert(modified()?, snapshot))
+    }
+
+    /// The cached snapshot, if it was read from a file last modified at `modified`.
+    pub fn get(&self, modified: SystemTime) -> Option<Arc<LoadedSnapshot>> {
+        self.loaded
+            .read()
+            .unwrap()
+            .clone()
+            .filter(|loaded| loaded.modified == modified)
+    }
+
+    /// Replaces the cached snapshot, dropping the wishlists generated from the previous one.
+    pub fn insert(&self, modified: SystemTime, snapshot: Snapshot) -> Arc<LoadedSnapshot> {
+        let loaded = Arc::new(LoadedSnapshot {
+            modified,
+            snapshot,
+            wishlists: WishlistCache::default(),
+        });
+        *self.loaded.write().unwrap() = Some(loaded.clone());
+
+        loaded
+    }
+}
+
+pub struct SnapshotDiff<'a> {
+    pub added: Vec<&'a Weapon>,
+    pub removed: Vec<&'a Weapon>,
+    pub changed: Vec<(&'a Weapon, &'a Weapon)>,
+}
+
+impl SnapshotDiff<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Converts the newline separated `column_1` and `column_2` of older snapshots into
+/// unresolved perks, without hashes or descriptions until the next refresh.
+fn migrate(snapshot: &mut Value) {
+    let Some(weapons) = snapshot.get_mut("weapons").and_then(Value::as_array_mut) else {
+        return;
+    };
+
+    for weapon in weapons.iter_mut().filter_map(Value::as_object_mut) {
+        if weapon.contains_key("perks") {
+            continue;
+        }
+
+        let perks = ["column_1", "column_2"].map(|key| {
+            let column = weapon.remove(key);
+
+            column
+                .as_ref()
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .split('\n')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .enumerate()
+                .map(|(priority, name)| Perk {
+                    name: String::from(name),
+                    priority,
+                    hashes: Vec::new(),
+                    enhanced: false,
+                    description: String::new(),
+                })
+                .collect::<Vec<_>>()
+        });
+
+        if let Ok(perks) = serde_json::to_value(perks) {
+            weapon.insert(String::from("perks"), perks);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize, Serialize)]
+pub enum Stat {
+    Impact,
+    Range,
+    Stability,
+    Handling,
+    ReloadSpeed,
+    Magazine,
+    RoundsPerMinute,
+}
+
+impl Stat {
+    pub const ALL: [Stat; 7] = [
+        Stat::Impact,
+        Stat::Range,
+        Stat::Stability,
+        Stat::Handling,
+        Stat::ReloadSpeed,
+        Stat::Magazine,
+        Stat::RoundsPerMinute,
+    ];
+
+    /// The `DestinyStatDefinition` hash in the Bungie manifest.
+    pub fn hash(self) -> u32 {
+        match self {
+            Stat::Impact => 4043523819,
+            Stat::Range => 1240592695,
+            Stat::Stability => 155624089,
+            Stat::Handling => 943549884,
+            Stat::ReloadSpeed => 4188031367,
+            Stat::Magazine => 3871231066,
+            Stat::RoundsPerMinute => 4284893193,
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Stat::Impact => "impact",
+            Stat::Range => "range",
+            Stat::Stability => "stability",
+            Stat::Handling => "handling",
+            Stat::ReloadSpeed => "reload",
+            Stat::Magazine => "magazine",
+            Stat::RoundsPerMinute => "rpm",
+        }
+    }
+}
+
+impl FromStr for Stat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|stat| {
+                stat.id().eq_ignore_ascii_case(s) || stat.to_string().eq_ignore_ascii_case(s)
+            })
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stat::Impact => write!(f, "Impact"),
+            Stat::Range => write!(f, "Range"),
+            Stat::Stability => write!(f, "Stability"),
+            Stat::Handling => write!(f, "Handling"),
+            Stat::ReloadSpeed => write!(f, "Reload Speed"),
+            Stat::Magazine => write!(f, "Magazine"),
+            Stat::RoundsPerMinute => write!(f, "RPM"),
+        }
+    }
+}
+
+/// Base investment stats of a single item hash. Stats the item does not have are `None`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, FromRow, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WeaponStats {
+    #[sqlx(default)]
+    pub impact: Option<i64>,
+    #[sqlx(default)]
+    pub range: Option<i64>,
+    #[sqlx(default)]
+    pub stability: Option<i64>,
+    #[sqlx(default)]
+    pub handling: Option<i64>,
+    #[sqlx(default)]
+    pub reload_speed: Option<i64>,
+    #[sqlx(default)]
+    pub magazine: Option<i64>,
+    #[sqlx(default)]
+    pub rounds_per_minute: Option<i64>,
+}
+
+impl WeaponStats {
+    pub fn get(&self, stat: Stat) -> Option<i64> {
+        match stat {
+            Stat::Impact => self.impact,
+            Stat::Range => self.range,
+            Stat::Stability => self.stability,
+            Stat::Handling => self.handling,
+            Stat::ReloadSpeed => self.reload_speed,
+            Stat::Magazine => self.magazine,
+            Stat::RoundsPerMinute => self.rounds_per_minute,
+        }
+    }
+
+    pub fn set(&mut self, stat: Stat, value: Option<i64>) {
+        match stat {
+            Stat::Impact => self.impact = value,
+            Stat::Range => self.range = value,
+            Stat::Stability => self.stability = value,
+            Stat::Handling => self.handling = value,
+            Stat::ReloadSpeed => self.reload_speed = value,
+            Stat::Magazine => self.magazine = value,
+            Stat::RoundsPerMinute => self.rounds_per_minute = value,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        Stat::ALL.into_iter().all(|stat| self.get(stat).is_none())
+    }
+
+    /// The known stats as `(stat, value)` pairs in display order.
+    pub fn iter(&self) -> impl Iterator<Item = (Stat, i64)> + '_ {
+        Stat::ALL
+            .into_iter()
+            .filter_map(|stat| Some((stat, self.get(stat)?)))
+    }
+}
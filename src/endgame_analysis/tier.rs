@@ -2,7 +2,6 @@ use std::{fmt, str::FromStr};
 
 use google_sheets_api::types::sheet::CellData;
 use serde::{Deserialize, Serialize};
-use serenity::all::Colour;
 
 pub const TIERS: [TierLabel; 7] = [
     TierLabel::S,
@@ -17,7 +16,8 @@ pub const TIERS: [TierLabel; 7] = [
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Tier {
     pub tier: TierLabel,
-    pub colour: Colour,
+    /// The tier cell's background as `0xRRGGBB`.
+    pub colour: u32,
 }
 
 impl Tier {
@@ -26,25 +26,27 @@ impl Tier {
     }
 }
 
-impl From<CellData> for Tier {
-    fn from(value: CellData) -> Self {
-        let tier = value.formatted_value.unwrap().parse().unwrap();
+impl TryFrom<CellData> for Tier {
+    type Error = ();
+
+    fn try_from(value: CellData) -> Result<Self, Self::Error> {
+        let tier = value.formatted_value.ok_or(())?.parse()?;
         let colour = value
             .effective_format
-            .unwrap()
-            .background_color_style
-            .unwrap()
-            .rgb_color
-            .unwrap();
+            .and_then(|f| f.background_color_style)
+            .and_then(|s| s.rgb_color)
+            .ok_or(())?;
 
-        Self {
+        Ok(Self {
             tier,
-            colour: google_colour_to_serde_colour(colour),
-        }
+            colour: google_colour_to_rgb(&colour),
+        })
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy, Deserialize, Serialize)]
+#[derive(
+    Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize,
+)]
 pub enum TierLabel {
     S,
     A,
@@ -57,6 +59,13 @@ pub enum TierLabel {
     None,
 }
 
+impl TierLabel {
+    /// Tiers are ordered best first, so `S` is the smallest and `None` never passes a cutoff.
+    pub fn is_at_least(self, min: TierLabel) -> bool {
+        self != TierLabel::None && self <= min
+    }
+}
+
 impl FromStr for TierLabel {
     type Err = ();
 
@@ -89,16 +98,78 @@ impl fmt::Display for TierLabel {
     }
 }
 
-fn google_colour_to_serde_colour(
-    colour: google_sheets_api::types::common::Color,
-) -> serenity::all::Colour {
-    fn f64_to_u8(value: f64) -> u8 {
-        (value.clamp(0.0, 1.0) * 255.0).round() as u8
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Strictness {
+    #[default]
+    Soft,
+    Regular,
+    SemiStrict,
+    Strict,
+    VeryStrict,
+    UberStrict,
+}
+
+impl Strictness {
+    pub const ALL: [Strictness; 6] = [
+        Strictness::Soft,
+        Strictness::Regular,
+        Strictness::SemiStrict,
+        Strictness::Strict,
+        Strictness::VeryStrict,
+        Strictness::UberStrict,
+    ];
+
+    pub fn min_tier(self) -> TierLabel {
+        match self {
+            Strictness::Soft => TierLabel::F,
+            Strictness::Regular => TierLabel::D,
+            Strictness::SemiStrict => TierLabel::C,
+            Strictness::Strict => TierLabel::B,
+            Strictness::VeryStrict => TierLabel::A,
+            Strictness::UberStrict => TierLabel::S,
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Strictness::Soft => "soft",
+            Strictness::Regular => "regular",
+            Strictness::SemiStrict => "semi-strict",
+            Strictness::Strict => "strict",
+            Strictness::VeryStrict => "very strict",
+            Strictness::UberStrict => "uber strict",
+        }
+    }
+}
+
+impl FromStr for Strictness {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|strictness| strictness.id() == s)
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Strictness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Strictness::Soft => write!(f, "Soft"),
+            Strictness::Regular => write!(f, "Regular"),
+            Strictness::SemiStrict => write!(f, "Semi-strict"),
+            Strictness::Strict => write!(f, "Strict"),
+            Strictness::VeryStrict => write!(f, "Very Strict"),
+            Strictness::UberStrict => write!(f, "Uber Strict"),
+        }
+    }
+}
+
+pub(crate) fn google_colour_to_rgb(colour: &google_sheets_api::types::common::Color) -> u32 {
+    fn f64_to_u32(value: f64) -> u32 {
+        (value.clamp(0.0, 1.0) * 255.0).round() as u32
     }
 
-    Colour::from_rgb(
-        f64_to_u8(colour.red),
-        f64_to_u8(colour.green),
-        f64_to_u8(colour.blue),
-    )
+    (f64_to_u32(colour.red) << 16) | (f64_to_u32(colour.green) << 8) | f64_to_u32(colour.blue)
 }
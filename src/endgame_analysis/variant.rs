@@ -0,0 +1,117 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Database, Pool};
+
+use crate::{DestinyWeapon, DestinyWeaponManager};
+
+#[derive(
+    Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize,
+)]
+pub enum Variant {
+    #[default]
+    Base,
+    /// Another item with the base name, e.g. a sunset original or a reissue.
+    #[serde(alias = "Reissue")]
+    Duplicate,
+    Adept,
+    Timelost,
+    Harrowed,
+    Brave,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 6] = [
+        Variant::Base,
+        Variant::Duplicate,
+        Variant::Adept,
+        Variant::Timelost,
+        Variant::Harrowed,
+        Variant::Brave,
+    ];
+
+    /// Finds every item hash for `name` and classifies it, dropping unrelated weapons that
+    /// only share the name as a prefix.
+    ///
+    /// Of the items with the exact name, the base is the one that can still roll perks, with
+    /// the lowest hash breaking ties. The others are [`Variant::Duplicate`]s.
+    pub async fn resolve<Db: Database, Manager: DestinyWeaponManager<Db>>(
+        pool: &Pool<Db>,
+        name: &str,
+    ) -> sqlx::Result<Vec<(Variant, DestinyWeapon)>> {
+        let mut weapons = Manager::get_by_prefix(pool, name)
+            .await?
+            .into_iter()
+            .filter_map(|w| Some((Self::classify(name, &w.name)?, w)))
+            .collect::<Vec<_>>();
+
+        if weapons.is_empty() {
+            return Err(sqlx::Error::RowNotFound);
+        }
+
+        let base = weapons
+            .iter()
+            .filter(|(variant, _)| *variant == Variant::Base)
+            .min_by_key(|(_, w)| (!w.can_roll(), w.id))
+            .map(|(_, w)| w.id);
+
+        for (variant, w) in &mut weapons {
+            if *variant == Variant::Base && base != Some(w.id) {
+                *variant = Variant::Duplicate;
+            }
+        }
+
+        weapons.sort_by_key(|(variant, w)| (*variant, w.id));
+
+        Ok(weapons)
+    }
+
+    /// Ignores case, as the managers do when looking names up.
+    pub fn classify(base: &str, name: &str) -> Option<Variant> {
+        let suffix = name
+            .get(..base.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(base))
+            .and(name.get(base.len()..))?;
+
+        if suffix.is_empty() {
+            return Some(Variant::Base);
+        }
+
+        match suffix.trim().to_ascii_lowercase().as_str() {
+            "(adept)" => Some(Variant::Adept),
+            "(timelost)" => Some(Variant::Timelost),
+            "(harrowed)" => Some(Variant::Harrowed),
+            "(brave)" => Some(Variant::Brave),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Variant {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Base" => Ok(Variant::Base),
+            "Duplicate" | "Reissue" => Ok(Variant::Duplicate),
+            "Adept" => Ok(Variant::Adept),
+            "Timelost" => Ok(Variant::Timelost),
+            "Harrowed" => Ok(Variant::Harrowed),
+            "Brave" => Ok(Variant::Brave),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Variant::Base => write!(f, "Base"),
+            Variant::Duplicate => write!(f, "Duplicate"),
+            Variant::Adept => write!(f, "Adept"),
+            Variant::Timelost => write!(f, "Timelost"),
+            Variant::Harrowed => write!(f, "Harrowed"),
+            Variant::Brave => write!(f, "Brave"),
+        }
+    }
+}
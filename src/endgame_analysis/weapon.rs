@@ -1,14 +1,13 @@
-use std::{collections::HashMap, ops::Deref};
+use std::collections::HashMap;
 
-use futures::{StreamExt, stream};
 use google_sheets_api::types::sheet::{CellData, RowData};
 use serde::{Deserialize, Serialize};
-use serenity::all::{AutocompleteChoice, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter};
 use sqlx::{Database, Pool};
 
-use crate::{DestinyPerkManager, DestinyWeaponManager};
+use crate::wishlist::{WishlistEntry, WishlistOptions};
+use crate::{DestinyPerkManager, DestinyWeapon, DestinyWeaponManager, Error, Result};
 
-use super::{Affinity, Frame, Tier};
+use super::{Affinity, Ammo, Archetype, Frame, Perk, RefreshIssue, Tier, Variant, WeaponStats};
 
 // const IDEAL_SHOTGUN_COLUMN: IdealWeaponColumns = IdealWeaponColumns {
 //     column_1: Column1::BarrelShroud,
@@ -62,7 +61,9 @@ use super::{Affinity, Frame, Tier};
 #[derive(Default)]
 pub struct WeaponBuilder {
     pub name: String,
-    pub archetype: String,
+    pub variant: Variant,
+    pub archetype: Archetype,
+    pub ammo: Ammo,
     pub affinity: String,
     pub frame: Option<String>,
     pub enhanceable: bool,
@@ -76,9 +77,16 @@ pub struct WeaponBuilder {
 }
 
 impl WeaponBuilder {
-    pub fn new(name: impl Into<String>, archetype: impl Into<String>) -> Self {
+    pub fn new(name: impl Into<String>, archetype: Archetype) -> Self {
         let name = name.into();
 
+        let variant = match name.trim() {
+            // Only the BRAVE version is listed, without the marker.
+            "Elsie's Rifle" => Variant::Brave,
+            name if name.contains("BRAVE version") => Variant::Brave,
+            _ => Variant::Base,
+        };
+
         let name = match name.as_str() {
             "Song of Ir Yut" => String::from("Song of Ir Yût"),
             "Fang of Ir Yut" => String::from("Fang of Ir Yût"),
@@ -89,23 +97,42 @@ impl WeaponBuilder {
             "Lunulata-4B" => String::from("Lunulata-4b"),
             "IKELOS_HC_V1.0.3" => String::from("IKELOS_HC_v1.0.3"),
             "IKELOS_SMG_V1.0.3" => String::from("IKELOS_SMG_v1.0.3"),
-            "Elsie's Rifle" => String::from("Elsie's Rifle (Brave)"),
             "Jararaca-3SR" => String::from("Jararaca-3sr"),
             "Redback-5SI" => String::from("Redback-5si"),
             "Judgement" => String::from("Judgment"),
             name => name
                 .trim()
-                .replace("\nBRAVE version", " (Brave)")
-                .replace(" (BRAVE version)", " (Brave)"),
+                .replace("\nBRAVE version", "")
+                .replace(" (BRAVE version)", ""),
+        };
+
+        // Keeps the BRAVE row apart from the base row in wishlists, queries and diffs.
+        let name = match variant {
+            Variant::Brave => format!("{} (Brave)", name),
+            _ => name,
         };
 
         WeaponBuilder {
             name,
-            archetype: archetype.into(),
+            variant,
+            archetype,
             ..Default::default()
         }
     }
 
+    /// The name without the " (Brave)" suffix of a BRAVE row.
+    pub fn base_name(&self) -> &str {
+        match self.variant {
+            Variant::Brave => self.name.strip_suffix(" (Brave)").unwrap_or(&self.name),
+            _ => &self.name,
+        }
+    }
+
+    pub fn ammo(mut self, ammo: Ammo) -> Self {
+        self.ammo = ammo;
+        self
+    }
+
     pub fn affinity(mut self, affinity: impl Into<String>) -> Self {
         self.affinity = affinity.into();
         self
@@ -156,7 +183,12 @@ impl WeaponBuilder {
         self
     }
 
-    pub fn from_row_data(name: &str, header: &RowData, row: RowData) -> Option<Self> {
+    /// Reads a weapon row keyed by the header row. `None` for blank rows and the "Ideal" row.
+    pub fn from_row_data(
+        archetype: Archetype,
+        header: &RowData,
+        row: RowData,
+    ) -> Result<Option<Self>> {
         let mut data = header
             .values
             .iter()
@@ -172,79 +204,144 @@ impl WeaponBuilder {
             })
             .collect::<HashMap<String, CellData>>();
 
-        let weapon_name = data.remove("name").unwrap().formatted_value.unwrap();
+        let Some(weapon_name) = data.remove("name").and_then(|c| c.formatted_value) else {
+            return Ok(None);
+        };
 
         if weapon_name == "Ideal" {
-            return None;
+            return Ok(None);
         }
 
-        let reserves = data
-            .remove("reserves")
-            .map(|r| r.formatted_value.unwrap())
+        let mut optional = |column: &str| data.remove(column).and_then(|c| c.formatted_value);
+        let reserves = optional("reserves")
             .filter(|s| s != "?")
-            .map(|s| s.parse().unwrap());
-        let shield = data
-            .remove("shield")
-            .map(|r| r.formatted_value.unwrap())
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| Error::Parse(format!("reserves '{}' for {}", s, weapon_name)))
+            })
+            .transpose()?;
+        let shield = optional("shield")
             .filter(|s| s != "?")
-            .map(|s| s.parse().unwrap());
-        let archetype = match name {
-            "BGLs" | "HGLs" => String::from("Grenade Launcher"),
-            "LMGs" => String::from("Machine Gun"),
-            "LFRs" => String::from("Linear Fusion"),
-            "HCs" => String::from("Hand Cannon"),
-            s => String::from(&s[..s.len() - 1]),
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| Error::Parse(format!("shield '{}' for {}", s, weapon_name)))
+            })
+            .transpose()?;
+        let frame = optional("frame");
+
+        let mut required = |column: &str| {
+            data.remove(column)
+                .and_then(|c| c.formatted_value)
+                .ok_or_else(|| Error::Parse(format!("missing {} for {}", column, weapon_name)))
         };
+        let affinity = required("affinity")?;
+        let enhanceable = required("enhance")? == "Yes";
+        let column_1 = required("column 1")?;
+        let column_2 = required("column 2")?;
+        let origin_trait = required("origin trait")?;
+        let rank = required("rank")?;
+        let rank = rank
+            .parse()
+            .map_err(|_| Error::Parse(format!("rank '{}' for {}", rank, weapon_name)))?;
+
+        let tier = data
+            .remove("tier")
+            .ok_or(())
+            .and_then(Tier::try_from)
+            .map_err(|_| Error::Parse(format!("tier for {}", weapon_name)))?;
 
         let weapon = Self::new(weapon_name, archetype)
-            .affinity(data.remove("affinity").unwrap().formatted_value.unwrap())
-            .frame(data.remove("frame").map(|f| f.formatted_value.unwrap()))
-            .enhanceable(data.remove("enhance").unwrap().formatted_value.unwrap() == "Yes")
+            .affinity(affinity)
+            .frame(frame)
+            .enhanceable(enhanceable)
             .shield(shield)
             .reserves(reserves)
-            .column_1(data.remove("column 1").unwrap().formatted_value.unwrap())
-            .column_2(data.remove("column 2").unwrap().formatted_value.unwrap())
-            .origin_trait(
-                data.remove("origin trait")
-                    .unwrap()
-                    .formatted_value
-                    .unwrap(),
-            )
-            .rank(
-                data.remove("rank")
-                    .unwrap()
-                    .formatted_value
-                    .unwrap()
-                    .parse()
-                    .unwrap(),
-            )
-            .tier(data.remove("tier").unwrap());
-
-        Some(weapon)
-    }
-
-    pub async fn build<Db: Database, Manager: DestinyWeaponManager<Db>>(
+            .column_1(column_1)
+            .column_2(column_2)
+            .origin_trait(origin_trait)
+            .rank(rank)
+            .tier(tier);
+
+        Ok(Some(weapon))
+    }
+
+    pub async fn build<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
         self,
         pool: &Pool<Db>,
-    ) -> sqlx::Result<Weapon> {
-        let icon = Manager::get(pool, &self.name).await?.icon;
+    ) -> Result<(Weapon, Vec<RefreshIssue>)> {
+        // A BRAVE row only covers the BRAVE items, the base row every other variant.
+        let brave = self.variant == Variant::Brave;
+        let variants = Variant::resolve::<Db, WeaponManager>(pool, self.base_name())
+            .await?
+            .into_iter()
+            .filter(|(variant, _)| (*variant == Variant::Brave) == brave)
+            .collect::<Vec<_>>();
+
+        if variants.is_empty() {
+            return Err(Error::Database(sqlx::Error::RowNotFound));
+        }
+
+        let item = &variants
+            .iter()
+            .find(|(variant, _)| *variant == self.variant)
+            .unwrap_or(&variants[0])
+            .1;
+        let icon = item.icon.clone();
+        let stats = item.stats;
+
+        let pools = variants
+            .iter()
+            .map(|(_, w)| [&w.perk_1, &w.perk_2])
+            .collect::<Vec<_>>();
+
+        let mut perks = [
+            Perk::resolve_column::<Db, PerkManager>(pool, &self.column_1).await?,
+            Perk::resolve_column::<Db, PerkManager>(pool, &self.column_2).await?,
+        ];
+        for (i, column) in perks.iter_mut().enumerate() {
+            let pools = pools.iter().map(|pool| pool[i]).collect::<Vec<_>>();
+            column
+                .iter_mut()
+                .for_each(|perk| perk.detect_enhanced(&pools));
+        }
 
-        let weapon = Weapon {
+        let affinity = self
+            .affinity
+            .parse()
+            .map_err(|_| Error::Parse(format!("affinity '{}' for {}", self.affinity, self.name)))?;
+        let frame = self
+            .frame
+            .map(|f| {
+                f.parse()
+                    .map_err(|_| Error::Parse(format!("frame '{}' for {}", f, self.name)))
+            })
+            .transpose()?;
+
+        let mut weapon = Weapon {
             icon,
             name: self.name,
             archetype: self.archetype,
-            affinity: self.affinity.parse().unwrap(),
-            frame: self.frame.map(|f| f.parse().unwrap()),
+            ammo: self.ammo,
+            affinity,
+            frame,
             enhanceable: self.enhanceable,
             reserves: self.reserves,
-            column_1: self.column_1,
-            column_2: self.column_2,
+            stats,
+            perks,
             origin_trait: self.origin_trait,
             rank: self.rank,
             tier: self.tier,
+            items: Vec::new(),
         };
 
-        Ok(weapon)
+        let (items, issues) = weapon.as_api(variants);
+        weapon.items = items;
+
+        Ok((weapon, issues))
     }
 }
 
@@ -252,16 +349,19 @@ impl WeaponBuilder {
 pub struct Weapon {
     pub icon: String,
     pub name: String,
-    pub archetype: String,
+    pub archetype: Archetype,
+    pub ammo: Ammo,
     pub affinity: Affinity,
     pub frame: Option<Frame>,
     pub enhanceable: bool,
     pub reserves: Option<u16>,
-    column_1: String,
-    column_2: String,
+    #[serde(default)]
+    pub stats: WeaponStats,
+    perks: [Vec<Perk>; 2],
     pub origin_trait: String,
     pub rank: u8,
     pub tier: Tier,
+    pub items: Vec<ApiWeapon>,
 }
 
 impl Weapon {
@@ -269,213 +369,347 @@ impl Weapon {
         &self.name
     }
 
-    pub fn archetype(&self) -> &str {
-        &self.archetype
+    pub fn archetype(&self) -> Archetype {
+        self.archetype
     }
 
-    pub fn perks(&self) -> Perks {
-        let column_1 = self.column_1.split('\n').collect::<Vec<_>>();
-        let column_2 = self.column_2.split('\n').collect::<Vec<_>>();
-
-        Perks([column_1, column_2])
+    pub fn perks(&self) -> &[Vec<Perk>; 2] {
+        &self.perks
     }
 
     pub fn origin_trait(&self) -> &str {
         &self.origin_trait
     }
 
-    pub async fn as_api<
-        Db: Database,
-        WeaponManager: DestinyWeaponManager<Db>,
-        PerkManager: DestinyPerkManager<Db>,
-    >(
+    /// The items of each variant with the perks they can roll, and what could not be resolved.
+    pub fn as_api(
         &self,
-        pool: &Pool<Db>,
-    ) -> Vec<ApiWeapon> {
-        let name = self.name();
+        weapons: Vec<(Variant, DestinyWeapon)>,
+    ) -> (Vec<ApiWeapon>, Vec<RefreshIssue>) {
+        let mut issues = Vec::new();
 
-        let weapons = WeaponManager::get_by_prefix(pool, name).await.unwrap();
+        let api_perks = ApiPerks::new(&self.perks);
+
+        let unresolved = api_perks.unresolved();
+        if !unresolved.is_empty() {
+            issues.push(RefreshIssue::UnresolvedPerks(
+                unresolved.into_iter().map(String::from).collect(),
+            ));
+        }
 
-        if weapons.is_empty() {
-            panic!("No weapon found for {}", name);
+        let missing = api_perks.missing_from(weapons.iter().map(|(_, w)| [&w.perk_1, &w.perk_2]));
+        if !missing.is_empty() {
+            issues.push(RefreshIssue::PerksNotInPool(
+                missing.into_iter().map(String::from).collect(),
+            ));
         }
 
-        let api_perks = self.perks().as_api::<Db, PerkManager>(pool).await;
+        let items = weapons
+            .into_iter()
+            .filter_map(|(variant, w)| {
+                let perks = api_perks.filter_pool([&w.perk_1, &w.perk_2]);
+
+                if perks.is_empty() {
+                    issues.push(RefreshIssue::NoPerkCombination(w.id as u32));
+                    return None;
+                }
+
+                Some(ApiWeapon {
+                    hash: w.id as u32,
+                    variant,
+                    trash: perks.complement([&w.perk_1, &w.perk_2]),
+                    perks,
+                })
+            })
+            .collect();
+
+        (items, issues)
+    }
+
+    pub fn variants(&self) -> Vec<Variant> {
+        let mut variants = self
+            .items
+            .iter()
+            .map(|item| item.variant)
+            .collect::<Vec<_>>();
+        variants.dedup();
+        variants
+    }
+
+    pub fn wishlist_items<'a>(
+        &'a self,
+        options: &'a WishlistOptions,
+    ) -> impl Iterator<Item = &'a ApiWeapon> {
+        self.items
+            .iter()
+            .filter(|item| !options.exclude_variants.contains(&item.variant))
+    }
+
+    /// Builds the keep (and optionally trash) lines for every included item hash, keeping at
+    /// most `max_lines` of the highest priority combinations.
+    pub fn wishlist_entries(&self, source: &str, options: &WishlistOptions) -> Vec<WishlistEntry> {
+        let notes = self.notes(source);
+        let trash_rolls = options.trash_rolls_for(self.tier.tier);
+
+        let mut lines = self
+            .wishlist_items(options)
+            .flat_map(|item| {
+                let keep = item
+                    .perks
+                    .combinations()
+                    .into_iter()
+                    .map(move |(priority, perks)| (false, priority, item.hash, perks));
+
+                let trash = item
+                    .trash
+                    .combinations()
+                    .into_iter()
+                    .filter(move |_| trash_rolls)
+                    .map(move |(priority, perks)| (true, priority, item.hash, perks));
+
+                keep.chain(trash)
+            })
+            .enumerate()
+            .collect::<Vec<_>>();
+
+        if let Some(max_lines) = options.max_lines {
+            lines.sort_by_key(|(i, (trash, priority, ..))| (*trash, *priority, *i));
+            lines.truncate(max_lines);
+            lines.sort_by_key(|(i, _)| *i);
+        }
 
-        weapons
+        lines
             .into_iter()
-            .map(|w| ApiWeapon {
-                hash: w.id as u32,
-                perks: api_perks.clone(),
+            .map(|(_, (trash, _, item_hash, perks))| WishlistEntry {
+                item_hash: Some(item_hash),
+                perks,
+                trash,
+                comment: Some(self.name.clone()),
+                notes: Some(notes.clone()),
             })
             .collect()
     }
 
-    pub async fn as_wishlist<
-        Db: Database,
-        WeaponManager: DestinyWeaponManager<Db>,
-        PerkManager: DestinyPerkManager<Db>,
-    >(
-        &self,
-        pool: &Pool<Db>,
-    ) -> String {
-        let weapons = self.as_api::<Db, WeaponManager, PerkManager>(pool).await;
+    pub fn estimate_lines(&self, options: &WishlistOptions) -> usize {
+        let lines = self
+            .wishlist_items(options)
+            .map(|item| {
+                let trash = if options.trash_rolls_for(self.tier.tier) {
+                    item.trash.count()
+                } else {
+                    0
+                };
+                item.perks.count() + trash
+            })
+            .sum::<usize>();
 
-        let mut s = format!("// {}\n//notes: tags:pve", self.name);
+        lines.min(options.max_lines.unwrap_or(usize::MAX))
+    }
 
-        let perks = stream::iter(weapons)
-            .then(|w| async move { w.perks.as_wishlist(w.hash).await })
-            .collect::<Vec<_>>()
-            .await
-            .join("\n");
-        s.push_str(&perks);
+    pub fn trash_entries(&self, source: &str, options: &WishlistOptions) -> Vec<WishlistEntry> {
+        let notes = self.notes(source);
 
-        s
+        self.wishlist_items(options)
+            .map(|item| WishlistEntry {
+                item_hash: Some(item.hash),
+                perks: Vec::new(),
+                trash: true,
+                comment: Some(self.name.clone()),
+                notes: Some(notes.clone()),
+            })
+            .collect()
     }
-}
 
-impl From<&Weapon> for CreateEmbed {
-    fn from(value: &Weapon) -> Self {
-        let frame = value
+    pub fn notes(&self, source: &str) -> String {
+        let frame = self
             .frame
             .as_ref()
             .map(|f| format!("{} ", f))
             .unwrap_or_default();
 
-        let mut description = format!("Tier: {} (#{})", value.tier.tier(), value.rank);
-        if let Some(reserves) = value.reserves {
-            description.push_str(&format!("\nReserves: {}", reserves));
-        }
-
-        let embed = CreateEmbed::new()
-            .author(CreateEmbedAuthor::new(format!(
-                "{} {}{}",
-                value.affinity,
-                frame,
-                value.archetype(),
-            )))
-            .title(value.name.to_string())
-            .thumbnail(format!("https://www.bungie.net{}", value.icon))
-            .footer(CreateEmbedFooter::new("From 'Destiny 2: Endgame Analysis'"))
-            .colour(value.tier.colour)
-            .description(description)
-            .fields(
-                value
-                    .perks()
+        let perks = self
+            .perks()
+            .iter()
+            .map(|column| {
+                column
                     .iter()
-                    .enumerate()
-                    .map(|(i, p)| (i + 1, p))
-                    .map(|(i, p)| {
-                        (
-                            i,
-                            p.iter()
-                                .enumerate()
-                                .map(|(i, line)| format!("{}. {}", i + 1, line))
-                                .collect::<Vec<_>>(),
-                        )
-                    })
-                    .map(|(i, p)| (format!("Perk {}", i), p.join("\n"), true)),
-            )
-            .field("Origin Trait", value.origin_trait(), false);
-
-        embed
+                    .map(|perk| perk.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>()
+            .join(" / ");
+
+        format!(
+            "Tier {} (#{}) {} {}{}. Perks: {}. Origin: {}. Endgame Analysis {} |tags:pve,tier-{}",
+            self.tier.tier(),
+            self.rank,
+            self.affinity,
+            frame,
+            self.archetype,
+            perks,
+            self.origin_trait,
+            source,
+            self.tier.tier().to_lowercase(),
+        )
     }
 }
 
-impl From<Weapon> for AutocompleteChoice {
-    fn from(value: Weapon) -> Self {
-        AutocompleteChoice::new(value.name.clone(), value.name)
-    }
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApiWeapon {
+    pub hash: u32,
+    pub variant: Variant,
+    pub perks: ApiPerks,
+    pub trash: ApiPerks,
 }
 
-pub struct Perks<'a>([Vec<&'a str>; 2]);
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiPerk {
+    pub name: String,
+    pub hashes: Vec<u32>,
+}
 
-impl Perks<'_> {
-    pub async fn as_api<Db: Database, Manager: DestinyPerkManager<Db>>(
-        &self,
-        pool: &Pool<Db>,
-    ) -> ApiPerks {
-        async fn get_perk_ids<Db: Database, Manager: DestinyPerkManager<Db>>(
-            pool: &Pool<Db>,
-            perks: Vec<String>,
-        ) -> Vec<u32> {
-            let perk_records = Manager::get_all(pool, &perks).await.unwrap();
-
-            perk_records
-                .into_iter()
-                .map(|perk| perk.id as u32)
-                .collect()
-        }
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiPerks(Vec<Vec<ApiPerk>>);
+
+impl ApiPerks {
+    pub fn new(perks: &[Vec<Perk>; 2]) -> Self {
+        let perks = perks
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|perk| ApiPerk {
+                        name: perk.name.clone(),
+                        hashes: perk.hashes.clone(),
+                    })
+                    .collect()
+            })
+            .collect();
 
-        let iter = self
+        Self(perks)
+    }
+
+    /// Keeps only the perk hashes each column can actually roll on a specific item hash.
+    pub fn filter_pool(&self, pools: [&Vec<i64>; 2]) -> Self {
+        let perks = self
             .0
             .iter()
-            .map(|p| p.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+            .zip(pools)
+            .map(|(column, pool)| {
+                column
+                    .iter()
+                    .map(|perk| ApiPerk {
+                        name: perk.name.clone(),
+                        hashes: perk
+                            .hashes
+                            .iter()
+                            .copied()
+                            .filter(|hash| pool.contains(&(*hash as i64)))
+                            .collect(),
+                    })
+                    .filter(|perk| !perk.hashes.is_empty())
+                    .collect()
+            })
+            .collect();
 
-        let api_perks = stream::iter(iter)
-            .then(|perks| get_perk_ids::<Db, Manager>(pool, perks))
-            .collect::<Vec<_>>()
-            .await;
+        Self(perks)
+    }
 
-        ApiPerks(api_perks)
+    /// Returns `true` if any column has no perks, meaning no combination can roll.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty() || self.0.iter().any(|column| column.is_empty())
     }
-}
 
-impl<'a> Deref for Perks<'a> {
-    type Target = [Vec<&'a str>; 2];
+    /// Returns the perks in each pool column that are not recommended.
+    pub fn complement(&self, pools: [&Vec<i64>; 2]) -> Self {
+        let perks = self
+            .0
+            .iter()
+            .zip(pools)
+            .map(|(column, pool)| {
+                pool.iter()
+                    .map(|hash| *hash as u32)
+                    .filter(|hash| !column.iter().any(|perk| perk.hashes.contains(hash)))
+                    .map(|hash| ApiPerk {
+                        name: String::new(),
+                        hashes: vec![hash],
+                    })
+                    .collect()
+            })
+            .collect();
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+        Self(perks)
     }
-}
 
-#[derive(Debug)]
-pub struct ApiWeapon {
-    pub hash: u32,
-    pub perks: ApiPerks,
-}
+    /// Returns the names that did not resolve to any perk hash.
+    pub fn unresolved(&self) -> Vec<&str> {
+        self.0
+            .iter()
+            .flatten()
+            .filter(|perk| perk.hashes.is_empty())
+            .map(|perk| perk.name.as_str())
+            .collect()
+    }
 
-#[derive(Debug, Clone)]
-pub struct ApiPerks(Vec<Vec<u32>>);
+    /// Returns the perks that are not present in the matching column of any of the given pools.
+    pub fn missing_from<'a>(&self, pools: impl Iterator<Item = [&'a Vec<i64>; 2]>) -> Vec<&str> {
+        let pools = pools.collect::<Vec<_>>();
 
-impl ApiPerks {
-    pub async fn as_wishlist(&self, item_hash: u32) -> String {
-        fn generate_wishlist(
-            item_hash: u32,
-            perks: &[Vec<u32>],
-            s: &mut String,
-            current_perks: &mut Vec<u32>,
-            depth: usize,
-        ) {
-            if depth == perks.len() {
-                s.push_str("\ndimwishlist:item=");
-                s.push_str(&item_hash.to_string());
-                s.push_str("&perks=");
-                s.push_str(
-                    &current_perks
+        self.0
+            .iter()
+            .enumerate()
+            .flat_map(|(i, column)| column.iter().map(move |perk| (i, perk)))
+            .filter(|(_, perk)| !perk.hashes.is_empty())
+            .filter(|(i, perk)| {
+                !pools.iter().any(|pool| {
+                    perk.hashes
                         .iter()
-                        .copied()
-                        .map(|p| p.to_string())
-                        .collect::<Vec<_>>()
-                        .join(","),
-                );
-            } else {
-                for perk in &perks[depth] {
-                    current_perks.push(*perk);
-                    generate_wishlist(item_hash, perks, s, current_perks, depth + 1);
-                    current_perks.pop();
-                }
-            }
+                        .any(|hash| pool[*i].contains(&(*hash as i64)))
+                })
+            })
+            .map(|(_, perk)| perk.name.as_str())
+            .collect()
+    }
+
+    /// Every combination of one perk hash per column with its priority, lowest (best) first.
+    ///
+    /// A combination's priority is the sum of its perks' positions in the sheet's columns.
+    pub fn combinations(&self) -> Vec<(usize, Vec<u32>)> {
+        if self.0.is_empty() {
+            return Vec::new();
         }
 
-        let mut s = String::new();
-        match self.0.len() {
-            0 => String::new(),
-            len => {
-                let mut current_perks = Vec::with_capacity(len);
-                generate_wishlist(item_hash, &self.0, &mut s, &mut current_perks, 0);
-                s
-            }
+        let mut combinations = self
+            .0
+            .iter()
+            .fold(vec![(0, Vec::new())], |combinations, column| {
+                combinations
+                    .iter()
+                    .flat_map(|(priority, combination)| {
+                        column.iter().enumerate().flat_map(move |(i, perk)| {
+                            perk.hashes.iter().map(move |hash| {
+                                let mut combination = combination.clone();
+                                combination.push(*hash);
+                                (priority + i, combination)
+                            })
+                        })
+                    })
+                    .collect()
+            });
+
+        combinations.sort_by_key(|(priority, _)| *priority);
+        combinations
+    }
+
+    pub fn count(&self) -> usize {
+        if self.0.is_empty() {
+            return 0;
         }
+
+        self.0
+            .iter()
+            .map(|column| column.iter().map(|perk| perk.hashes.len()).sum::<usize>())
+            .product()
     }
 }
@@ -1,16 +1,163 @@
+#[cfg(feature = "discord")]
+use serenity::all::{
+    Colour, CommandInteraction, Context, CreateEmbed, CreateInteractionResponseFollowup,
+};
+
+use std::time::Duration;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
     WeaponNotFound(String),
+    InvalidWishlistLine(usize, String),
+    Sheets(SheetsError),
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Database(sqlx::Error),
+    Parse(String),
+    #[cfg(feature = "discord")]
+    Discord(serenity::Error),
+    Config(String),
+}
+
+#[cfg(feature = "discord")]
+impl Error {
+    /// Replaces the (possibly deferred) response with an ephemeral error embed.
+    pub async fn respond(
+        &self,
+        ctx: &Context,
+        interaction: &CommandInteraction,
+    ) -> serenity::Result<()> {
+        let embed = CreateEmbed::new()
+            .title("Something went wrong")
+            .description(self.to_string())
+            .colour(Colour::RED);
+
+        if let Err(e) = interaction.delete_response(ctx).await {
+            eprintln!("Failed to delete the deferred response: {}", e);
+        }
+
+        interaction
+            .create_followup(
+                ctx,
+                CreateInteractionResponseFollowup::new()
+                    .embed(embed)
+                    .ephemeral(true),
+            )
+            .await?;
+
+        Ok(())
+    }
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::WeaponNotFound(weapon) => write!(f, "Weapon {} not found", weapon),
+            Self::InvalidWishlistLine(line, content) => {
+                write!(f, "Invalid wishlist line {}: {}", line, content)
+            }
+            Self::Sheets(e) => write!(f, "Failed to fetch the Endgame Analysis sheet: {}", e),
+            Self::Io(e) => write!(f, "File error: {}", e),
+            Self::Json(e) => write!(f, "JSON error: {}", e),
+            Self::Database(e) => write!(f, "Database error: {}", e),
+            Self::Parse(e) => write!(f, "Failed to parse {}", e),
+            #[cfg(feature = "discord")]
+            Self::Discord(e) => write!(f, "Discord error: {}", e),
+            Self::Config(e) => write!(f, "Configuration error: {}", e),
         }
     }
 }
 
-impl std::error::Error for Error {}
+/// Why fetching the spreadsheet failed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SheetsError {
+    /// The API answered with a non-success status.
+    Status {
+        status: u16,
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// The request could not be sent, the connection dropped or a single attempt timed out.
+    Network(String),
+    /// The response was not a spreadsheet.
+    Decode(String),
+    /// Every attempt together took longer than the overall timeout.
+    Timeout,
+}
+
+impl SheetsError {
+    /// Rate limits, server errors and network failures are worth another attempt.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Status { status, .. } => matches!(status, 408 | 429 | 500 | 502 | 503 | 504),
+            Self::Network(_) => true,
+            Self::Decode(_) | Self::Timeout => false,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Status { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SheetsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Status {
+                status, message, ..
+            } => write!(f, "HTTP {}: {}", status, message),
+            Self::Network(e) => write!(f, "network error: {}", e),
+            Self::Decode(e) => write!(f, "invalid response: {}", e),
+            Self::Timeout => write!(f, "timed out"),
+        }
+    }
+}
+
+impl From<SheetsError> for Error {
+    fn from(value: SheetsError) -> Self {
+        Self::Sheets(value)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::Database(e) => Some(e),
+            #[cfg(feature = "discord")]
+            Self::Discord(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(value: sqlx::Error) -> Self {
+        Self::Database(value)
+    }
+}
+
+#[cfg(feature = "discord")]
+impl From<serenity::Error> for Error {
+    fn from(value: serenity::Error) -> Self {
+        Self::Discord(value)
+    }
+}
@@ -1,19 +1,33 @@
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use sqlx::{Database, FromRow, Pool};
 
+use endgame_analysis::WeaponStats;
+
+#[cfg(feature = "discord")]
 pub mod dimwishlist;
 pub mod endgame_analysis;
 pub mod error;
+pub mod managers;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "discord")]
 pub mod tierlist;
+#[cfg(feature = "discord")]
 pub mod weapon;
+pub mod wishlist;
 
+#[cfg(feature = "discord")]
 pub use dimwishlist::DimWishlistCommand;
+#[cfg(feature = "discord")]
 pub use tierlist::TierListCommand;
+#[cfg(feature = "discord")]
 pub use weapon::WeaponCommand;
 
 pub use error::Error;
 use error::Result;
 
+/// Both lookups match names ignoring case, like [`DestinyPerkManager`].
 #[async_trait]
 pub trait DestinyWeaponManager<Db: Database> {
     async fn get(pool: &Pool<Db>, name: &str) -> sqlx::Result<DestinyWeapon>;
@@ -21,7 +35,7 @@ pub trait DestinyWeaponManager<Db: Database> {
     async fn get_by_prefix(pool: &Pool<Db>, name: &str) -> sqlx::Result<Vec<DestinyWeapon>>;
 }
 
-#[derive(FromRow)]
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
 pub struct DestinyWeapon {
     pub id: i64,
     pub icon: String,
@@ -30,6 +44,16 @@ pub struct DestinyWeapon {
     pub column_2: Vec<i64>,
     pub perk_1: Vec<i64>,
     pub perk_2: Vec<i64>,
+    #[sqlx(flatten)]
+    #[serde(default)]
+    pub stats: WeaponStats,
+}
+
+impl DestinyWeapon {
+    /// Whether both trait columns have plugs, which managers only list while they can roll.
+    pub fn can_roll(&self) -> bool {
+        !self.perk_1.is_empty() && !self.perk_2.is_empty()
+    }
 }
 
 #[async_trait]
@@ -39,7 +63,7 @@ pub trait DestinyPerkManager<Db: Database> {
     async fn get_all(pool: &Pool<Db>, names: &[String]) -> sqlx::Result<Vec<DestinyPerk>>;
 }
 
-#[derive(FromRow)]
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
 pub struct DestinyPerk {
     pub id: i64,
     pub name: String,
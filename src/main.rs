@@ -0,0 +1,307 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use endgame_analysis::Error;
+use endgame_analysis::endgame_analysis::{
+    Archetype, EndgameAnalysisSheet, EndgameConfig, Snapshot, SortKey, Stat, Strictness, TIERS,
+    TierLabel, Weapon, WeaponQuery,
+};
+use endgame_analysis::error::Result;
+use endgame_analysis::managers::SqliteManager;
+use endgame_analysis::wishlist::{Wishlist, WishlistDocument, WishlistFormat, WishlistOptions};
+use sqlx::SqlitePool;
+
+#[derive(Parser)]
+#[command(
+    name = "endgame-analysis",
+    about = "Query the Destiny 2: Endgame Analysis sheet"
+)]
+struct Cli {
+    /// SQLite database holding the `destiny_weapons` and `destiny_perks` tables
+    #[arg(long, env = "DATABASE_URL", default_value = "sqlite://destiny.db")]
+    database: String,
+
+    /// TOML file overriding the spreadsheet, credentials, tabs and snapshot path
+    #[arg(long, env = "ENDGAME_ANALYSIS_CONFIG")]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Rebuild the weapon snapshot
+    Refresh {
+        /// Read a saved Sheets API response instead of fetching the sheet
+        #[arg(long)]
+        dump: Option<PathBuf>,
+    },
+    /// Show a single weapon
+    Weapon { name: String },
+    /// Show the tier list for an archetype
+    Tierlist {
+        archetype: String,
+        /// The tier to display up to
+        #[arg(long, default_value = "F")]
+        tier: String,
+        /// The number of weapons to display
+        #[arg(long)]
+        count: Option<usize>,
+        /// Sort each tier by a stat: impact, range, stability, handling, reload, magazine or rpm
+        #[arg(long)]
+        sort: Option<String>,
+    },
+    /// Export a wishlist
+    Wishlist {
+        /// soft, regular, semi-strict, strict, very strict or uber strict
+        #[arg(long, default_value = "soft")]
+        strictness: String,
+        /// dim, littlelight or json
+        #[arg(long, default_value = "dim")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Compare two snapshot files
+    Diff { old: PathBuf, new: PathBuf },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let config = match cli.config {
+        Some(path) => EndgameConfig::load(path)?,
+        None => EndgameConfig::from_env()?,
+    };
+
+    let pool = SqlitePool::connect_lazy(&cli.database)?;
+
+    match cli.command {
+        Command::Refresh { dump } => {
+            let report = match dump {
+                Some(path) => {
+                    EndgameAnalysisSheet::update_from_dump::<_, SqliteManager, SqliteManager>(
+                        &pool, &config, path,
+                    )
+                    .await?
+                }
+                None => {
+                    EndgameAnalysisSheet::update::<_, SqliteManager, SqliteManager>(&pool, &config)
+                        .await?
+                }
+            };
+
+            let snapshot = Snapshot::read(&config)?;
+            println!("Refreshed {} weapons ({})", report.weapons, snapshot.date());
+
+            if !report.issues.is_empty() {
+                println!("\n{} issues:", report.issues.len());
+            }
+            for (weapon, issue) in &report.issues {
+                println!("{}: {}", weapon, issue);
+            }
+        }
+        Command::Weapon { name } => {
+            let snapshot =
+                Snapshot::load::<_, SqliteManager, SqliteManager>(&pool, &config).await?;
+
+            let weapon = WeaponQuery::new()
+                .name(Some(name.as_str()))
+                .limit(Some(1))
+                .run(&snapshot.weapons)
+                .pop()
+                .ok_or_else(|| Error::WeaponNotFound(name.clone()))?;
+
+            print_weapon(weapon);
+        }
+        Command::Tierlist {
+            archetype,
+            tier,
+            count,
+            sort,
+        } => {
+            let archetype = archetype
+                .parse::<Archetype>()
+                .map_err(|_| Error::Parse(format!("archetype '{}'", archetype)))?;
+            let min_tier = tier
+                .parse::<TierLabel>()
+                .map_err(|_| Error::Parse(format!("tier '{}'", tier)))?;
+            let stat = sort
+                .map(|stat| {
+                    stat.parse::<Stat>()
+                        .map_err(|_| Error::Parse(format!("stat '{}'", stat)))
+                })
+                .transpose()?;
+
+            let snapshot =
+                Snapshot::load::<_, SqliteManager, SqliteManager>(&pool, &config).await?;
+
+            let weapons = WeaponQuery::new()
+                .archetype(Some(archetype))
+                .min_tier(Some(min_tier))
+                .sort(stat.map_or(SortKey::Tier, SortKey::Stat))
+                .limit(count)
+                .run(&snapshot.weapons);
+
+            println!("Tier List for {}", archetype);
+
+            for tier in TIERS {
+                let weapons = weapons
+                    .iter()
+                    .filter(|w| w.tier.tier == tier)
+                    .collect::<Vec<_>>();
+
+                if weapons.is_empty() {
+                    continue;
+                }
+
+                println!("\n{}", tier);
+                for (i, weapon) in weapons.iter().enumerate() {
+                    match stat.and_then(|stat| weapon.stats.get(stat)) {
+                        Some(value) => println!("{}. {} ({})", i + 1, weapon.name(), value),
+                        None => println!("{}. {}", i + 1, weapon.name()),
+                    }
+                }
+            }
+        }
+        Command::Wishlist {
+            strictness,
+            format,
+            output,
+        } => {
+            let strictness = strictness
+                .parse::<Strictness>()
+                .map_err(|_| Error::Parse(format!("strictness '{}'", strictness)))?;
+            let format = format
+                .parse::<WishlistFormat>()
+                .map_err(|_| Error::Parse(format!("format '{}'", format)))?;
+
+            let snapshot =
+                Snapshot::load::<_, SqliteManager, SqliteManager>(&pool, &config).await?;
+            let mut wishlist =
+                Wishlist::generate(&snapshot, &WishlistOptions::from_strictness(strictness));
+
+            // Only DIM files carry the header, and an unchanged one should not show up in diffs.
+            let previous = output
+                .as_ref()
+                .filter(|_| format == WishlistFormat::Dim)
+                .and_then(|path| fs::read_to_string(path).ok())
+                .and_then(|previous| previous.parse::<WishlistDocument>().ok());
+            if let Some(previous) = &previous {
+                wishlist.keep_generated(previous);
+            }
+
+            let wishlist = format.serializer().serialize(&wishlist);
+
+            match output {
+                Some(path) => fs::write(path, wishlist)?,
+                None => print!("{}", wishlist),
+            }
+        }
+        Command::Diff { old, new } => {
+            let old = Snapshot::read_from(old)?;
+            let new = Snapshot::read_from(new)?;
+            let diff = old.diff(&new);
+
+            if diff.is_empty() {
+                println!("No changes");
+            }
+
+            for weapon in diff.added {
+                println!(
+                    "+ {} ({}, Tier {})",
+                    weapon.name(),
+                    weapon.archetype(),
+                    weapon.tier.tier()
+                );
+            }
+            for weapon in diff.removed {
+                println!(
+                    "- {} ({}, Tier {})",
+                    weapon.name(),
+                    weapon.archetype(),
+                    weapon.tier.tier()
+                );
+            }
+            for (old, new) in diff.changed {
+                let mut changes = Vec::new();
+
+                if old.tier.tier != new.tier.tier || old.rank != new.rank {
+                    changes.push(format!(
+                        "Tier {} (#{}) -> Tier {} (#{})",
+                        old.tier.tier(),
+                        old.rank,
+                        new.tier.tier(),
+                        new.rank
+                    ));
+                }
+                if old.perks() != new.perks() {
+                    changes.push(String::from("perks changed"));
+                }
+
+                println!(
+                    "~ {} ({}): {}",
+                    new.name(),
+                    new.archetype(),
+                    changes.join(", ")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_weapon(weapon: &Weapon) {
+    let frame = weapon
+        .frame
+        .as_ref()
+        .map(|f| format!("{} ", f))
+        .unwrap_or_default();
+
+    println!("{}", weapon.name());
+    println!("{} {}{}", weapon.affinity, frame, weapon.archetype());
+    println!("Tier: {} (#{})", weapon.tier.tier(), weapon.rank);
+    if let Some(reserves) = weapon.reserves {
+        println!("Reserves: {}", reserves);
+    }
+
+    if !weapon.stats.is_empty() {
+        println!("\nStats");
+        for (stat, value) in weapon.stats.iter() {
+            println!("{}: {}", stat, value);
+        }
+    }
+
+    for (i, perks) in weapon.perks().iter().enumerate() {
+        println!("\nPerk {}", i + 1);
+        for (i, perk) in perks.iter().enumerate() {
+            println!("{}. {}", i + 1, perk);
+        }
+    }
+
+    println!("\nOrigin Trait: {}", weapon.origin_trait());
+
+    let variants = weapon
+        .variants()
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!("Variants: {}", variants);
+}
@@ -0,0 +1,22 @@
+pub mod manifest;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use manifest::ManifestManager;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresManager;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteManager;
+
+/// Escapes `LIKE` wildcards with `\` and appends `%` to match names starting with `prefix`.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+fn like_prefix(prefix: &str) -> String {
+    let mut pattern = prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    pattern.push('%');
+    pattern
+}
@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use sqlx::{Database, Pool};
+
+use crate::endgame_analysis::{Stat, WeaponStats};
+use crate::{DestinyPerk, DestinyPerkManager, DestinyWeapon, DestinyWeaponManager, Result};
+
+const WEAPON_ITEM_TYPE: i32 = 3;
+const WEAPON_PERKS_SOCKET_CATEGORY: u32 = 4241085061;
+
+/// Plug categories of the first column, e.g. sword blades and bow strings.
+const BARREL_PLUG_CATEGORIES: [&str; 6] = [
+    "barrels",
+    "blades",
+    "bowstrings",
+    "hafts",
+    "scopes",
+    "tubes",
+];
+/// Plug categories of the second column, e.g. fusion batteries and sword guards.
+const MAGAZINE_PLUG_CATEGORIES: [&str; 5] =
+    ["magazines", "magazines_gl", "batteries", "arrows", "guards"];
+const TRAIT_PLUG_CATEGORY: &str = "frames";
+
+tokio::task_local! {
+    static MANIFEST: Arc<ManifestManager>;
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemDefinition {
+    hash: u32,
+    display_properties: DisplayProperties,
+    #[serde(default)]
+    item_type: i32,
+    sockets: Option<Sockets>,
+    stats: Option<ItemStats>,
+    plug: Option<PlugDefinition>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlugDefinition {
+    #[serde(default)]
+    plug_category_identifier: String,
+}
+
+#[derive(Deserialize)]
+struct ItemStats {
+    #[serde(default)]
+    stats: HashMap<String, StatValue>,
+}
+
+#[derive(Deserialize)]
+struct StatValue {
+    value: i64,
+}
+
+impl ItemStats {
+    fn weapon_stats(&self) -> WeaponStats {
+        let mut stats = WeaponStats::default();
+
+        for stat in Stat::ALL {
+            let value = self.stats.get(&stat.hash().to_string()).map(|s| s.value);
+            stats.set(stat, value);
+        }
+
+        stats
+    }
+}
+
+#[derive(Deserialize)]
+struct DisplayProperties {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    icon: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Sockets {
+    socket_entries: Vec<SocketEntry>,
+    #[serde(default)]
+    socket_categories: Vec<SocketCategory>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SocketEntry {
+    #[serde(default)]
+    single_initial_item_hash: u32,
+    randomized_plug_set_hash: Option<u32>,
+    reusable_plug_set_hash: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SocketCategory {
+    socket_category_hash: u32,
+    socket_indexes: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlugSetDefinition {
+    #[serde(default)]
+    reusable_plug_items: Vec<PlugItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlugItem {
+    plug_item_hash: u32,
+    #[serde(default = "can_roll")]
+    currently_can_roll: bool,
+}
+
+fn can_roll() -> bool {
+    true
+}
+
+/// The `en` world content file: every definition table keyed by hash.
+#[derive(Deserialize)]
+struct WorldContent {
+    #[serde(rename = "DestinyInventoryItemDefinition")]
+    items: HashMap<String, ItemDefinition>,
+    #[serde(rename = "DestinyPlugSetDefinition")]
+    plug_sets: HashMap<String, PlugSetDefinition>,
+}
+
+/// Serves weapons and perks from a locally downloaded Bungie manifest.
+///
+/// The manager traits take no instance, so lookups read the manager of the enclosing
+/// [`ManifestManager::scope`] and the pool is never queried. Names are indexed lowercase.
+#[derive(Default)]
+pub struct ManifestManager {
+    weapons: BTreeMap<String, Vec<DestinyWeapon>>,
+    perks: HashMap<String, Vec<DestinyPerk>>,
+}
+
+impl ManifestManager {
+    fn new(items: Vec<ItemDefinition>, plug_sets: HashMap<u32, PlugSetDefinition>) -> Self {
+        let items = items
+            .into_iter()
+            .map(|item| (item.hash, item))
+            .collect::<HashMap<_, _>>();
+
+        let mut manifest = Self::default();
+
+        for item in items.values() {
+            if item.item_type != WEAPON_ITEM_TYPE || item.display_properties.name.is_empty() {
+                continue;
+            }
+
+            let Some(sockets) = &item.sockets else {
+                continue;
+            };
+
+            // Origin traits, trackers and the like share the category, and the socket order
+            // differs between weapons, so columns are told apart by their plugs.
+            let mut column_1 = None;
+            let mut column_2 = None;
+            let mut traits = Vec::new();
+
+            for entry in sockets
+                .socket_categories
+                .iter()
+                .filter(|category| category.socket_category_hash == WEAPON_PERKS_SOCKET_CATEGORY)
+                .flat_map(|category| category.socket_indexes.iter())
+                .filter_map(|i| sockets.socket_entries.get(*i))
+            {
+                let plugs = plugs(entry, &plug_sets);
+
+                match plug_category(&plugs, &items) {
+                    Some(category) if BARREL_PLUG_CATEGORIES.contains(&category) => {
+                        column_1 = column_1.or(Some(plugs));
+                    }
+                    Some(category) if MAGAZINE_PLUG_CATEGORIES.contains(&category) => {
+                        column_2 = column_2.or(Some(plugs));
+                    }
+                    Some(TRAIT_PLUG_CATEGORY) => traits.push(plugs),
+                    _ => {}
+                }
+            }
+
+            let mut traits = traits.into_iter();
+
+            let weapon = DestinyWeapon {
+                id: item.hash as i64,
+                icon: item.display_properties.icon.clone(),
+                name: item.display_properties.name.clone(),
+                column_1: column_1.unwrap_or_default(),
+                column_2: column_2.unwrap_or_default(),
+                perk_1: traits.next().unwrap_or_default(),
+                perk_2: traits.next().unwrap_or_default(),
+                stats: item
+                    .stats
+                    .as_ref()
+                    .map(ItemStats::weapon_stats)
+                    .unwrap_or_default(),
+            };
+
+            for hash in [&weapon.perk_1, &weapon.perk_2].into_iter().flatten() {
+                manifest.add_perk(*hash as u32, &items);
+            }
+
+            manifest
+                .weapons
+                .entry(weapon.name.to_lowercase())
+                .or_default()
+                .push(weapon);
+        }
+
+        for weapons in manifest.weapons.values_mut() {
+            weapons.sort_by_key(|w| w.id);
+        }
+
+        manifest
+    }
+
+    fn add_perk(&mut self, hash: u32, items: &HashMap<u32, ItemDefinition>) {
+        let Some(item) = items.get(&hash) else {
+            return;
+        };
+
+        let perks = self
+            .perks
+            .entry(item.display_properties.name.to_lowercase())
+            .or_default();
+
+        if perks.iter().all(|perk| perk.id != hash as i64) {
+            perks.push(DestinyPerk {
+                id: hash as i64,
+                name: item.display_properties.name.clone(),
+                description: item.display_properties.description.clone(),
+            });
+        }
+    }
+
+    /// Loads the JSON world content file listed under `jsonWorldContentPaths`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let content = serde_json::from_str::<WorldContent>(&json)?;
+
+        let plug_sets = content
+            .plug_sets
+            .into_iter()
+            .filter_map(|(hash, plug_set)| Some((hash.parse().ok()?, plug_set)))
+            .collect();
+
+        Ok(Self::new(content.items.into_values().collect(), plug_sets))
+    }
+
+    /// Loads the SQLite content database listed under `mobileWorldContentPaths`.
+    #[cfg(feature = "sqlite")]
+    pub async fn load_sqlite(path: impl AsRef<Path>) -> Result<Self> {
+        use sqlx::sqlite::SqliteConnectOptions;
+        use sqlx::{Row, SqlitePool};
+
+        let options = SqliteConnectOptions::new().filename(path).read_only(true);
+        let pool = SqlitePool::connect_with(options).await?;
+
+        let items = sqlx::query("SELECT json FROM DestinyInventoryItemDefinition")
+            .fetch_all(&pool)
+            .await?
+            .into_iter()
+            .map(|row| serde_json::from_str::<ItemDefinition>(row.get("json")))
+            .collect::<serde_json::Result<Vec<_>>>()?;
+
+        let plug_sets = sqlx::query("SELECT id, json FROM DestinyPlugSetDefinition")
+            .fetch_all(&pool)
+            .await?
+            .into_iter()
+            .map(|row| {
+                // Hashes are stored as signed 32-bit ids.
+                let hash = row.get::<i64, _>("id") as i32 as u32;
+                serde_json::from_str::<PlugSetDefinition>(row.get("json")).map(|p| (hash, p))
+            })
+            .collect::<serde_json::Result<HashMap<_, _>>>()?;
+
+        pool.close().await;
+
+        Ok(Self::new(items, plug_sets))
+    }
+
+    /// Runs `f` with the [`DestinyWeaponManager`] and [`DestinyPerkManager`] lookups of
+    /// [`ManifestManager`] served from this manifest.
+    pub async fn scope<F: Future>(self: &Arc<Self>, f: F) -> F::Output {
+        MANIFEST.scope(self.clone(), f).await
+    }
+
+    fn with<T>(f: impl FnOnce(&ManifestManager) -> sqlx::Result<T>) -> sqlx::Result<T> {
+        MANIFEST
+            .try_with(|manifest| f(manifest))
+            .unwrap_or_else(|_| {
+                Err(sqlx::Error::Configuration(
+                    "ManifestManager used outside ManifestManager::scope".into(),
+                ))
+            })
+    }
+}
+
+fn plugs(entry: &SocketEntry, plug_sets: &HashMap<u32, PlugSetDefinition>) -> Vec<i64> {
+    let plug_set = entry
+        .randomized_plug_set_hash
+        .or(entry.reusable_plug_set_hash)
+        .and_then(|hash| plug_sets.get(&hash));
+
+    match plug_set {
+        Some(plug_set) => plug_set
+            .reusable_plug_items
+            .iter()
+            .filter(|plug| plug.currently_can_roll)
+            .map(|plug| plug.plug_item_hash as i64)
+            .collect(),
+        None if entry.single_initial_item_hash != 0 => {
+            vec![entry.single_initial_item_hash as i64]
+        }
+        None => Vec::new(),
+    }
+}
+
+/// The `plugCategoryIdentifier` of the first plug with a definition.
+fn plug_category<'a>(plugs: &[i64], items: &'a HashMap<u32, ItemDefinition>) -> Option<&'a str> {
+    plugs.iter().find_map(|hash| {
+        items
+            .get(&(*hash as u32))?
+            .plug
+            .as_ref()
+            .map(|plug| plug.plug_category_identifier.as_str())
+    })
+}
+
+#[async_trait]
+impl<Db: Database> DestinyWeaponManager<Db> for ManifestManager {
+    async fn get(_pool: &Pool<Db>, name: &str) -> sqlx::Result<DestinyWeapon> {
+        Self::with(|manifest| {
+            manifest
+                .weapons
+                .get(&name.to_lowercase())
+                .and_then(|weapons| weapons.first())
+                .cloned()
+                .ok_or(sqlx::Error::RowNotFound)
+        })
+    }
+
+    async fn get_by_prefix(_pool: &Pool<Db>, name: &str) -> sqlx::Result<Vec<DestinyWeapon>> {
+        let name = name.to_lowercase();
+
+        Self::with(|manifest| {
+            let weapons = manifest
+                .weapons
+                .range(name.clone()..)
+                .take_while(|(key, _)| key.starts_with(&name))
+                .flat_map(|(_, weapons)| weapons.iter().cloned())
+                .collect();
+
+            Ok(weapons)
+        })
+    }
+}
+
+#[async_trait]
+impl<Db: Database> DestinyPerkManager<Db> for ManifestManager {
+    async fn get(_pool: &Pool<Db>, name: &str) -> sqlx::Result<DestinyPerk> {
+        Self::with(|manifest| {
+            manifest
+                .perks
+                .get(&name.to_lowercase())
+                .and_then(|perks| perks.first())
+                .cloned()
+                .ok_or(sqlx::Error::RowNotFound)
+        })
+    }
+
+    async fn get_all(_pool: &Pool<Db>, names: &[String]) -> sqlx::Result<Vec<DestinyPerk>> {
+        Self::with(|manifest| {
+            let perks = names
+                .iter()
+                .filter_map(|name| manifest.perks.get(&name.to_lowercase()))
+                .flatten()
+                .cloned()
+                .collect();
+
+            Ok(perks)
+        })
+    }
+}
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+
+use crate::{DestinyPerk, DestinyPerkManager, DestinyWeapon, DestinyWeaponManager};
+
+use super::like_prefix;
+
+/// Reads weapons and perks from the tables created by the `migrations/postgres` migrations.
+pub struct PostgresManager;
+
+impl PostgresManager {
+    pub async fn migrate(pool: &Pool<Postgres>) -> sqlx::Result<()> {
+        let mut migrator = sqlx::migrate!("migrations/postgres");
+        // The tables usually live next to the host application's own migrations.
+        migrator.set_ignore_missing(true);
+        migrator.run(pool).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DestinyWeaponManager<Postgres> for PostgresManager {
+    async fn get(pool: &Pool<Postgres>, name: &str) -> sqlx::Result<DestinyWeapon> {
+        sqlx::query_as::<_, DestinyWeapon>(
+            "SELECT id, icon, name, column_1, column_2, perk_1, perk_2, impact, \"range\", stability, handling, reload_speed, magazine, rounds_per_minute FROM destiny_weapons WHERE lower(name) = lower($1) ORDER BY id LIMIT 1",
+        )
+        .bind(name)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn get_by_prefix(pool: &Pool<Postgres>, name: &str) -> sqlx::Result<Vec<DestinyWeapon>> {
+        sqlx::query_as::<_, DestinyWeapon>(
+            "SELECT id, icon, name, column_1, column_2, perk_1, perk_2, impact, \"range\", stability, handling, reload_speed, magazine, rounds_per_minute FROM destiny_weapons WHERE lower(name) LIKE $1 ORDER BY id",
+        )
+        .bind(like_prefix(&name.to_lowercase()))
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[async_trait]
+impl DestinyPerkManager<Postgres> for PostgresManager {
+    async fn get(pool: &Pool<Postgres>, name: &str) -> sqlx::Result<DestinyPerk> {
+        sqlx::query_as::<_, DestinyPerk>(
+            "SELECT id, name, description FROM destiny_perks WHERE lower(name) = lower($1) ORDER BY id LIMIT 1",
+        )
+        .bind(name)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn get_all(pool: &Pool<Postgres>, names: &[String]) -> sqlx::Result<Vec<DestinyPerk>> {
+        let names = names.iter().map(|n| n.to_lowercase()).collect::<Vec<_>>();
+
+        sqlx::query_as::<_, DestinyPerk>(
+            "SELECT id, name, description FROM destiny_perks WHERE lower(name) = ANY($1)",
+        )
+        .bind(names)
+        .fetch_all(pool)
+        .await
+    }
+}
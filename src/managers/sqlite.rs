@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use sqlx::types::Json;
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::endgame_analysis::WeaponStats;
+use crate::{DestinyPerk, DestinyPerkManager, DestinyWeapon, DestinyWeaponManager};
+
+use super::like_prefix;
+
+/// Reads weapons and perks from the tables created by the `migrations/sqlite` migrations.
+///
+/// SQLite has no array type, so the plug columns are stored as JSON arrays of hashes.
+/// Names use `NOCASE`, which only ignores the case of ASCII letters.
+pub struct SqliteManager;
+
+impl SqliteManager {
+    pub async fn migrate(pool: &Pool<Sqlite>) -> sqlx::Result<()> {
+        let mut migrator = sqlx::migrate!("migrations/sqlite");
+        // The tables usually live next to the host application's own migrations.
+        migrator.set_ignore_missing(true);
+        migrator.run(pool).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(FromRow)]
+struct WeaponRow {
+    id: i64,
+    icon: String,
+    name: String,
+    column_1: Json<Vec<i64>>,
+    column_2: Json<Vec<i64>>,
+    perk_1: Json<Vec<i64>>,
+    perk_2: Json<Vec<i64>>,
+    #[sqlx(flatten)]
+    stats: WeaponStats,
+}
+
+impl From<WeaponRow> for DestinyWeapon {
+    fn from(value: WeaponRow) -> Self {
+        Self {
+            id: value.id,
+            icon: value.icon,
+            name: value.name,
+            column_1: value.column_1.0,
+            column_2: value.column_2.0,
+            perk_1: value.perk_1.0,
+            perk_2: value.perk_2.0,
+            stats: value.stats,
+        }
+    }
+}
+
+#[async_trait]
+impl DestinyWeaponManager<Sqlite> for SqliteManager {
+    async fn get(pool: &Pool<Sqlite>, name: &str) -> sqlx::Result<DestinyWeapon> {
+        sqlx::query_as::<_, WeaponRow>(
+            "SELECT id, icon, name, column_1, column_2, perk_1, perk_2, impact, \"range\", stability, handling, reload_speed, magazine, rounds_per_minute FROM destiny_weapons WHERE name = ? ORDER BY id LIMIT 1",
+        )
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .map(DestinyWeapon::from)
+    }
+
+    async fn get_by_prefix(pool: &Pool<Sqlite>, name: &str) -> sqlx::Result<Vec<DestinyWeapon>> {
+        let rows = sqlx::query_as::<_, WeaponRow>(
+            "SELECT id, icon, name, column_1, column_2, perk_1, perk_2, impact, \"range\", stability, handling, reload_speed, magazine, rounds_per_minute FROM destiny_weapons WHERE name LIKE ? ESCAPE '\\' ORDER BY id",
+        )
+        .bind(like_prefix(name))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(DestinyWeapon::from).collect())
+    }
+}
+
+#[async_trait]
+impl DestinyPerkManager<Sqlite> for SqliteManager {
+    async fn get(pool: &Pool<Sqlite>, name: &str) -> sqlx::Result<DestinyPerk> {
+        sqlx::query_as::<_, DestinyPerk>(
+            "SELECT id, name, description FROM destiny_perks WHERE name = ? ORDER BY id LIMIT 1",
+        )
+        .bind(name)
+        .fetch_one(pool)
+        .await
+    }
+
+    async fn get_all(pool: &Pool<Sqlite>, names: &[String]) -> sqlx::Result<Vec<DestinyPerk>> {
+        sqlx::query_as::<_, DestinyPerk>(
+            "SELECT id, name, description FROM destiny_perks WHERE name IN (SELECT value FROM json_each(?))",
+        )
+        .bind(Json(names))
+        .fetch_all(pool)
+        .await
+    }
+}
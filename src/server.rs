@@ -0,0 +1,155 @@
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use axum::Router;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::{fs, task};
+
+use crate::endgame_analysis::{EndgameConfig, LoadedSnapshot, Snapshot, SnapshotCache, Strictness};
+use crate::wishlist::{DimFormat, WishlistOptions, WishlistSerializer};
+
+#[derive(Clone)]
+pub struct WishlistServer {
+    config: Arc<EndgameConfig>,
+    snapshots: Arc<SnapshotCache>,
+}
+
+impl WishlistServer {
+    pub fn new(config: EndgameConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            snapshots: Arc::default(),
+        }
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/wishlist/{file}", get(wishlist))
+            .route("/weapons.json", get(weapons))
+            .with_state(self)
+    }
+
+    pub async fn serve(self, addr: impl ToSocketAddrs) -> std::io::Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        axum::serve(listener, self.router()).await
+    }
+
+    async fn snapshot(&self) -> Option<Arc<LoadedSnapshot>> {
+        let modified = fs::metadata(&self.config.snapshot_path)
+            .await
+            .and_then(|m| m.modified())
+            .ok()?;
+
+        if let Some(loaded) = self.snapshots.get(modified) {
+            return Some(loaded);
+        }
+
+        let config = self.config.clone();
+        let snapshot = task::spawn_blocking(move || Snapshot::read(&config))
+            .await
+            .ok()?
+            .inspect_err(|e| eprintln!("Failed to read the snapshot: {}", e))
+            .ok()?;
+
+        Some(self.snapshots.insert(modified, snapshot))
+    }
+}
+
+async fn wishlist(
+    State(server): State<WishlistServer>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(strictness) = file.strip_suffix(".txt").and_then(|slug| {
+        Strictness::ALL
+            .into_iter()
+            .find(|s| s.id().replace(' ', "-") == slug)
+    }) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let Some(loaded) = server.snapshot().await else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+
+    let etag = format!(
+        "\"{}-{}\"",
+        version(&loaded),
+        strictness.id().replace(' ', "-")
+    );
+    if not_modified(&headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    let wishlist = task::spawn_blocking(move || {
+        let options = WishlistOptions::from_strictness(strictness);
+        DimFormat.serialize(&loaded.wishlists.generate(&loaded.snapshot, &options))
+    })
+    .await;
+    let Ok(wishlist) = wishlist else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    (
+        [
+            (header::ETAG, etag),
+            (
+                header::CONTENT_TYPE,
+                String::from("text/plain; charset=utf-8"),
+            ),
+        ],
+        wishlist,
+    )
+        .into_response()
+}
+
+async fn weapons(State(server): State<WishlistServer>, headers: HeaderMap) -> Response {
+    let Some(loaded) = server.snapshot().await else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+
+    let etag = format!("\"{}\"", version(&loaded));
+    if not_modified(&headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    let json = task::spawn_blocking(move || serde_json::to_string(&loaded.snapshot.weapons)).await;
+    let Ok(Ok(json)) = json else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    (
+        [
+            (header::ETAG, etag),
+            (header::CONTENT_TYPE, String::from("application/json")),
+        ],
+        json,
+    )
+        .into_response()
+}
+
+/// The snapshot file's modified time in nanoseconds, since refreshes can land within a second.
+fn version(loaded: &LoadedSnapshot) -> u128 {
+    loaded
+        .modified
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+/// Compares weakly, as `If-None-Match` requires, so `W/` validators match too.
+fn not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| {
+            v.split(',').map(str::trim).any(|t| {
+                let t = t.strip_prefix("W/").unwrap_or(t);
+                t == etag || t == "*"
+            })
+        })
+}
@@ -1,5 +1,4 @@
-use std::collections::{HashMap, HashSet};
-use std::fs;
+use std::collections::HashMap;
 
 use serenity::all::{
     AutocompleteChoice, AutocompleteOption, CommandInteraction, CommandOptionType, Context,
@@ -9,22 +8,58 @@ use serenity::all::{
 use sqlx::{Database, Pool};
 use zayden_core::parse_options;
 
-use crate::endgame_analysis::EndgameAnalysisSheet;
-use crate::endgame_analysis::weapon::Weapon;
-use crate::{DestinyWeaponManager, Result};
+use crate::endgame_analysis::{
+    Archetype, EndgameConfig, SnapshotCache, SortKey, Stat, WeaponQuery,
+};
+use crate::{DestinyPerkManager, DestinyWeaponManager, Error, Result};
 
-use super::endgame_analysis::tier::TIERS;
+use super::endgame_analysis::tier::{TIERS, TierLabel};
 
 pub struct TierListCommand;
 
 impl TierListCommand {
-    pub async fn run<Db: Database, Manager: DestinyWeaponManager<Db>>(
+    pub async fn run<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
         ctx: &Context,
         interaction: &CommandInteraction,
         options: Vec<ResolvedOption<'_>>,
         pool: &Pool<Db>,
+        config: &EndgameConfig,
+        snapshots: &SnapshotCache,
     ) -> Result<()> {
-        interaction.defer(ctx).await.unwrap();
+        let result = Self::execute::<Db, WeaponManager, PerkManager>(
+            ctx,
+            interaction,
+            options,
+            pool,
+            config,
+            snapshots,
+        )
+        .await;
+
+        if let Err(e) = &result {
+            e.respond(ctx, interaction).await?;
+        }
+
+        result
+    }
+
+    async fn execute<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
+        ctx: &Context,
+        interaction: &CommandInteraction,
+        options: Vec<ResolvedOption<'_>>,
+        pool: &Pool<Db>,
+        config: &EndgameConfig,
+        snapshots: &SnapshotCache,
+    ) -> Result<()> {
+        interaction.defer(ctx).await?;
 
         let mut options = parse_options(options);
 
@@ -32,41 +67,50 @@ impl TierListCommand {
             unreachable!("Archetype is required");
         };
 
+        let archetype = archetype
+            .parse::<Archetype>()
+            .map_err(|_| Error::Parse(format!("archetype '{}'", archetype)))?;
+
         let count = options.get("count").map(|c| match c {
             ResolvedValue::Integer(c) => *c as usize,
             _ => unreachable!("Count must be an integer"),
         });
 
-        let tiers = match options.get("tier") {
-            Some(ResolvedValue::String(tier)) => {
-                let tier = tier.parse().unwrap();
-                let index = TIERS.iter().copied().position(|t| t == tier).unwrap();
-                &TIERS[..=index]
-            }
-            _ => &TIERS,
+        let min_tier = match options.get("tier") {
+            Some(ResolvedValue::String(tier)) => tier
+                .parse()
+                .map_err(|_| Error::Parse(format!("tier '{}'", tier)))?,
+            _ => TierLabel::F,
         };
 
-        let weapons: Vec<Weapon> = if let Ok(w) = fs::read_to_string("weapons.json") {
-            serde_json::from_str(&w).unwrap()
-        } else {
-            EndgameAnalysisSheet::update::<Db, Manager>(pool).await?;
-            let w = fs::read_to_string("weapons.json").unwrap();
-            serde_json::from_str(&w).unwrap()
+        let stat = match options.get("sort") {
+            Some(ResolvedValue::String(stat)) => Some(
+                stat.parse::<Stat>()
+                    .map_err(|_| Error::Parse(format!("stat '{}'", stat)))?,
+            ),
+            _ => None,
         };
 
-        let init_map = tiers
+        let loaded = snapshots
+            .load::<Db, WeaponManager, PerkManager>(pool, config)
+            .await?;
+
+        let init_map = TIERS
             .iter()
             .copied()
+            .filter(|t| t.is_at_least(min_tier))
             .map(|t| (t, Vec::new()))
             .collect::<HashMap<_, _>>();
 
-        let weapons = weapons
+        let weapons = WeaponQuery::new()
+            .archetype(Some(archetype))
+            .min_tier(Some(min_tier))
+            .sort(stat.map_or(SortKey::Tier, SortKey::Stat))
+            .limit(count)
+            .run(&loaded.snapshot.weapons)
             .into_iter()
-            .filter(|w| w.archetype() == archetype)
-            .filter(|w| tiers.contains(&w.tier.tier))
-            .take(count.unwrap_or(usize::MAX))
             .fold(init_map, |mut map, w| {
-                map.get_mut(&w.tier.tier).unwrap().push(w.name);
+                map.get_mut(&w.tier.tier).unwrap().push(w);
                 map
             });
 
@@ -83,7 +127,14 @@ impl TierListCommand {
                 let weapons = weapons
                     .iter()
                     .enumerate()
-                    .map(|(i, w)| format!("{}. {}", i + 1, w))
+                    .map(|(i, w)| {
+                        let value = stat
+                            .and_then(|stat| w.stats.get(stat))
+                            .map(|v| format!(" ({})", v))
+                            .unwrap_or_default();
+
+                        format!("{}. {}{}", i + 1, w.name(), value)
+                    })
                     .collect::<Vec<_>>();
 
                 Some((t.to_string(), weapons.join("\n"), false))
@@ -91,8 +142,7 @@ impl TierListCommand {
 
         interaction
             .edit_response(ctx, EditInteractionResponse::new().embed(embed))
-            .await
-            .unwrap();
+            .await?;
 
         Ok(())
     }
@@ -107,6 +157,15 @@ impl TierListCommand {
             |option, tier| option.add_string_choice(tier.to_string(), tier.to_string()),
         );
 
+        let sort_option = Stat::ALL.iter().fold(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "sort",
+                "The stat to sort each tier by",
+            ),
+            |option, stat| option.add_string_choice(stat.to_string(), stat.id()),
+        );
+
         CreateCommand::new("tierlist")
             .description("Get a tier list of weapons from Destiny 2")
             .add_option(
@@ -124,31 +183,40 @@ impl TierListCommand {
                 "count",
                 "The number of weapons to display",
             ))
+            .add_option(sort_option)
     }
 
-    pub async fn autocomplete<Db: Database, Manager: DestinyWeaponManager<Db>>(
+    pub async fn autocomplete<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
         ctx: &Context,
         interaction: &CommandInteraction,
         option: AutocompleteOption<'_>,
         pool: &Pool<Db>,
+        config: &EndgameConfig,
+        snapshots: &SnapshotCache,
     ) -> Result<()> {
-        let weapons: Vec<Weapon> = match std::fs::read_to_string("weapons.json") {
-            Ok(weapons) => serde_json::from_str(&weapons).unwrap(),
-            Err(_) => {
-                EndgameAnalysisSheet::update::<Db, Manager>(pool).await?;
-                let weapons = std::fs::read_to_string("weapons.json").unwrap();
-                serde_json::from_str(&weapons).unwrap()
-            }
-        };
+        let loaded = snapshots
+            .load::<Db, WeaponManager, PerkManager>(pool, config)
+            .await?;
+        let weapons = &loaded.snapshot.weapons;
+
+        let value = option.value.to_lowercase();
 
         let choices = match option.name {
-            "archetype" => weapons
-                .iter()
-                .map(|w| w.archetype())
-                .collect::<HashSet<_>>()
+            "archetype" => Archetype::ALL
                 .into_iter()
-                .filter(|t| t.to_lowercase().contains(&option.value.to_lowercase()))
-                .map(AutocompleteChoice::from)
+                .filter(|a| weapons.iter().any(|w| w.archetype() == *a))
+                .filter(|a| {
+                    a.to_string().to_lowercase().contains(&value)
+                        || a.aliases()
+                            .iter()
+                            .any(|s| s.to_lowercase().contains(&value))
+                })
+                .map(|a| AutocompleteChoice::new(a.to_string(), a.to_string()))
+                .take(25)
                 .collect(),
             // "tier" => {
             //     tiers = TIERS
@@ -166,8 +234,7 @@ impl TierListCommand {
                     CreateAutocompleteResponse::new().set_choices(choices),
                 ),
             )
-            .await
-            .unwrap();
+            .await?;
 
         Ok(())
     }
@@ -1,28 +1,61 @@
-use std::fs;
-
 use serenity::all::{
     AutocompleteChoice, AutocompleteOption, CommandInteraction, CommandOptionType, Context,
-    CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateInteractionResponse,
-    EditInteractionResponse, ResolvedOption, ResolvedValue,
+    CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedAuthor,
+    CreateEmbedFooter, CreateInteractionResponse, EditInteractionResponse, ResolvedOption,
+    ResolvedValue,
 };
 use sqlx::{Database, Pool};
 use zayden_core::parse_options;
 
-use crate::{DestinyWeaponManager, Error, Result};
+use crate::{DestinyPerkManager, DestinyWeaponManager, Error, Result};
 
-use super::endgame_analysis::EndgameAnalysisSheet;
-use super::endgame_analysis::weapon::Weapon;
+use super::endgame_analysis::{EndgameConfig, SnapshotCache, Weapon, WeaponQuery};
 
 pub struct WeaponCommand;
 
 impl WeaponCommand {
-    pub async fn run<Db: Database, Manager: DestinyWeaponManager<Db>>(
+    pub async fn run<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
+        ctx: &Context,
+        interaction: &CommandInteraction,
+        options: Vec<ResolvedOption<'_>>,
+        pool: &Pool<Db>,
+        config: &EndgameConfig,
+        snapshots: &SnapshotCache,
+    ) -> Result<()> {
+        let result = Self::execute::<Db, WeaponManager, PerkManager>(
+            ctx,
+            interaction,
+            options,
+            pool,
+            config,
+            snapshots,
+        )
+        .await;
+
+        if let Err(e) = &result {
+            e.respond(ctx, interaction).await?;
+        }
+
+        result
+    }
+
+    async fn execute<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
         ctx: &Context,
         interaction: &CommandInteraction,
         _options: Vec<ResolvedOption<'_>>,
         pool: &Pool<Db>,
+        config: &EndgameConfig,
+        snapshots: &SnapshotCache,
     ) -> Result<()> {
-        interaction.defer(ctx).await.unwrap();
+        interaction.defer(ctx).await?;
 
         let options = interaction.data.options();
         let options = parse_options(options);
@@ -32,23 +65,20 @@ impl WeaponCommand {
             _ => unreachable!("Name is required"),
         };
 
-        let weapons: Vec<Weapon> = if let Ok(w) = fs::read_to_string("weapons.json") {
-            serde_json::from_str(&w).unwrap()
-        } else {
-            EndgameAnalysisSheet::update::<Db, Manager>(pool).await?;
-            let w = fs::read_to_string("weapons.json").unwrap();
-            serde_json::from_str(&w).unwrap()
-        };
+        let loaded = snapshots
+            .load::<Db, WeaponManager, PerkManager>(pool, config)
+            .await?;
 
-        let weapon = weapons
-            .iter()
-            .find(|&w| w.name().to_lowercase() == name.to_lowercase())
+        let weapon = WeaponQuery::new()
+            .name(Some(*name))
+            .limit(Some(1))
+            .run(&loaded.snapshot.weapons)
+            .pop()
             .ok_or_else(|| Error::WeaponNotFound(name.to_string()))?;
 
         interaction
             .edit_response(ctx, EditInteractionResponse::new().embed(weapon.into()))
-            .await
-            .unwrap();
+            .await?;
 
         Ok(())
     }
@@ -67,29 +97,28 @@ impl WeaponCommand {
             )
     }
 
-    pub async fn autocomplete<Db: Database, Manager: DestinyWeaponManager<Db>>(
+    pub async fn autocomplete<
+        Db: Database,
+        WeaponManager: DestinyWeaponManager<Db>,
+        PerkManager: DestinyPerkManager<Db>,
+    >(
         ctx: &Context,
         interaction: &CommandInteraction,
         option: AutocompleteOption<'_>,
         pool: &Pool<Db>,
+        config: &EndgameConfig,
+        snapshots: &SnapshotCache,
     ) -> Result<()> {
-        let weapons = match std::fs::read_to_string("weapons.json") {
-            Ok(weapons) => weapons,
-            Err(_) => {
-                EndgameAnalysisSheet::update::<Db, Manager>(pool).await?;
-                std::fs::read_to_string("weapons.json").unwrap()
-            }
-        };
-        let weapons: Vec<Weapon> = serde_json::from_str(&weapons).unwrap();
-        let weapons = weapons
+        let loaded = snapshots
+            .load::<Db, WeaponManager, PerkManager>(pool, config)
+            .await?;
+
+        let weapons = WeaponQuery::new()
+            .search(Some(option.value))
+            .limit(Some(25))
+            .run(&loaded.snapshot.weapons)
             .into_iter()
-            .filter(|w| {
-                w.name()
-                    .to_lowercase()
-                    .contains(&option.value.to_lowercase())
-            })
             .map(AutocompleteChoice::from)
-            .take(25)
             .collect::<Vec<_>>();
 
         interaction
@@ -99,9 +128,84 @@ impl WeaponCommand {
                     CreateAutocompleteResponse::new().set_choices(weapons),
                 ),
             )
-            .await
-            .unwrap();
+            .await?;
 
         Ok(())
     }
 }
+
+impl From<&Weapon> for CreateEmbed {
+    fn from(value: &Weapon) -> Self {
+        let frame = value
+            .frame
+            .as_ref()
+            .map(|f| format!("{} ", f))
+            .unwrap_or_default();
+
+        let mut description = format!("Tier: {} (#{})", value.tier.tier(), value.rank);
+        if let Some(reserves) = value.reserves {
+            description.push_str(&format!("\nReserves: {}", reserves));
+        }
+
+        let variants = value
+            .variants()
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let embed = CreateEmbed::new()
+            .author(CreateEmbedAuthor::new(format!(
+                "{} {}{}",
+                value.affinity,
+                frame,
+                value.archetype(),
+            )))
+            .title(value.name.to_string())
+            .thumbnail(format!("https://www.bungie.net{}", value.icon))
+            .footer(CreateEmbedFooter::new("From 'Destiny 2: Endgame Analysis'"))
+            .colour(value.tier.colour)
+            .description(description)
+            .fields(
+                value
+                    .perks()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (i + 1, p))
+                    .map(|(i, p)| {
+                        (
+                            i,
+                            p.iter()
+                                .enumerate()
+                                .map(|(i, perk)| {
+                                    let enhanced = if perk.enhanced { " (Enhanced)" } else { "" };
+                                    format!("{}. {}{}", i + 1, perk, enhanced)
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                    .map(|(i, p)| (format!("Perk {}", i), p.join("\n"), true)),
+            )
+            .field("Origin Trait", value.origin_trait(), false)
+            .field("Variants", variants, false);
+
+        if value.stats.is_empty() {
+            return embed;
+        }
+
+        let stats = value
+            .stats
+            .iter()
+            .map(|(stat, v)| format!("{}: {}", stat, v))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        embed.field("Stats", stats, false)
+    }
+}
+
+impl From<&Weapon> for AutocompleteChoice {
+    fn from(value: &Weapon) -> Self {
+        AutocompleteChoice::new(value.name(), value.name())
+    }
+}
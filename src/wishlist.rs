@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+pub mod format;
+pub mod parser;
+
+pub use format::{DimFormat, JsonFormat, LittleLightFormat, WishlistFormat, WishlistSerializer};
+pub use parser::{GENERATED_HEADER, WILDCARD_ITEM_HASH, WishlistDocument, WishlistEntry};
+
+use crate::endgame_analysis::{
+    Affinity, Ammo, Archetype, Frame, Snapshot, SortKey, Strictness, TIERS, TierLabel, Variant,
+    Weapon, WeaponQuery,
+};
+
+const TRASH_ROLL_TIERS: [TierLabel; 3] = [TierLabel::B, TierLabel::C, TierLabel::D];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WishlistOptions {
+    pub strictness: Option<Strictness>,
+    pub min_tier: TierLabel,
+    pub trash: Option<TierLabel>,
+    pub trash_rolls: bool,
+    pub max_lines: Option<usize>,
+    pub exclude_variants: Vec<Variant>,
+    pub archetype: Option<Archetype>,
+    pub affinities: Vec<Affinity>,
+    pub frame: Option<Frame>,
+    pub ammo: Option<Ammo>,
+}
+
+impl WishlistOptions {
+    pub fn new(min_tier: TierLabel) -> Self {
+        Self {
+            strictness: None,
+            min_tier,
+            trash: None,
+            trash_rolls: false,
+            max_lines: None,
+            exclude_variants: Vec::new(),
+            archetype: None,
+            affinities: Vec::new(),
+            frame: None,
+            ammo: None,
+        }
+    }
+
+    pub fn from_strictness(strictness: Strictness) -> Self {
+        Self {
+            strictness: Some(strictness),
+            ..Self::new(strictness.min_tier())
+        }
+    }
+
+    pub fn trash(mut self, trash: Option<TierLabel>) -> Self {
+        self.trash = trash;
+        self
+    }
+
+    pub fn trash_rolls(mut self, trash_rolls: bool) -> Self {
+        self.trash_rolls = trash_rolls;
+        self
+    }
+
+    pub fn max_lines(mut self, max_lines: Option<usize>) -> Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    pub fn exclude_variants(mut self, mut exclude_variants: Vec<Variant>) -> Self {
+        exclude_variants.sort();
+        exclude_variants.dedup();
+        self.exclude_variants = exclude_variants;
+        self
+    }
+
+    pub fn archetype(mut self, archetype: Option<Archetype>) -> Self {
+        self.archetype = archetype;
+        self
+    }
+
+    pub fn affinities(mut self, affinities: Vec<Affinity>) -> Self {
+        self.affinities = affinities;
+        self
+    }
+
+    pub fn frame(mut self, frame: Option<Frame>) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    pub fn ammo(mut self, ammo: Option<Ammo>) -> Self {
+        self.ammo = ammo;
+        self
+    }
+
+    pub fn label(&self) -> String {
+        let tier = match self.strictness {
+            Some(strictness) => strictness.to_string(),
+            None => format!("{} and above", self.min_tier),
+        };
+
+        std::iter::once(tier)
+            .chain(self.filters())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The title as a file name without an extension, e.g. `pve-wishlist-soft-arc-strand`.
+    pub fn file_name(&self) -> String {
+        format!("PVE Wishlist {}", self.label())
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase()
+    }
+
+    fn filters(&self) -> Vec<String> {
+        let mut filters = Vec::new();
+
+        if let Some(ammo) = self.ammo {
+            filters.push(ammo.to_string());
+        }
+        if !self.affinities.is_empty() {
+            filters.push(
+                self.affinities
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            );
+        }
+        if let Some(frame) = self.frame {
+            filters.push(frame.to_string());
+        }
+        if let Some(archetype) = self.archetype {
+            filters.push(archetype.to_string());
+        }
+        if !self.exclude_variants.is_empty() {
+            let variants = self
+                .exclude_variants
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>();
+            filters.push(format!("no {}", variants.join("/")));
+        }
+
+        filters
+    }
+
+    fn description(&self) -> String {
+        let mut description = format!(
+            "PVE rolls from Destiny 2: Endgame Analysis, tier {} and above",
+            self.min_tier
+        );
+
+        for filter in self.filters() {
+            description.push_str(", ");
+            description.push_str(&filter);
+        }
+
+        if let Some(trash) = self.trash_cutoff() {
+            description.push_str(&format!(". Tier {} and below marked as trash", trash));
+        }
+
+        description
+    }
+
+    /// The weapon filters, without the tier cutoffs since trash tiers are selected separately.
+    fn query(&self) -> WeaponQuery {
+        WeaponQuery::new()
+            .archetype(self.archetype)
+            .affinities(self.affinities.clone())
+            .frame(self.frame)
+            .ammo(self.ammo)
+            .sort(SortKey::Archetype)
+    }
+
+    /// The best tier marked as trash. Tiers that are kept never are, even if `trash` overlaps.
+    fn trash_cutoff(&self) -> Option<TierLabel> {
+        let trash = self.trash?;
+
+        TIERS
+            .into_iter()
+            .find(|tier| *tier >= trash && !tier.is_at_least(self.min_tier))
+    }
+
+    fn is_trash(&self, tier: TierLabel) -> bool {
+        self.trash_cutoff()
+            .is_some_and(|cutoff| tier != TierLabel::None && tier >= cutoff)
+    }
+
+    /// Unrecommended rolls are only marked as trash on mid tier weapons. Top tier weapons are
+    /// worth keeping regardless of roll, and bottom tier ones are trash as a whole.
+    pub fn trash_rolls_for(&self, tier: TierLabel) -> bool {
+        self.trash_rolls && TRASH_ROLL_TIERS.contains(&tier)
+    }
+}
+
+/// Wishlists generated from a single snapshot, keyed by their options.
+///
+/// Owned alongside the snapshot it was filled from and dropped with it, so a refreshed
+/// snapshot always starts with an empty cache.
+#[derive(Default)]
+pub struct WishlistCache {
+    wishlists: Mutex<HashMap<WishlistOptions, Arc<WishlistDocument>>>,
+}
+
+impl WishlistCache {
+    pub fn generate(
+        &self,
+        snapshot: &Snapshot,
+        options: &WishlistOptions,
+    ) -> Arc<WishlistDocument> {
+        if let Some(wishlist) = self.wishlists.lock().unwrap().get(options) {
+            return wishlist.clone();
+        }
+
+        let wishlist = Arc::new(Wishlist::generate(snapshot, options));
+        self.wishlists
+            .lock()
+            .unwrap()
+            .insert(options.clone(), wishlist.clone());
+
+        wishlist
+    }
+}
+
+pub struct Wishlist;
+
+impl Wishlist {
+    pub fn generate(snapshot: &Snapshot, options: &WishlistOptions) -> WishlistDocument {
+        Self::generate_at(snapshot, options, Utc::now())
+    }
+
+    /// Generates the wishlist with `generated` as its generation time.
+    pub fn generate_at(
+        snapshot: &Snapshot,
+        options: &WishlistOptions,
+        generated: DateTime<Utc>,
+    ) -> WishlistDocument {
+        Self::build(snapshot, options, generated)
+    }
+
+    pub fn render(
+        snapshot: &Snapshot,
+        options: &WishlistOptions,
+        format: &dyn WishlistSerializer,
+    ) -> String {
+        format.serialize(&Self::generate(snapshot, options))
+    }
+
+    /// Counts the lines a wishlist would contain without generating it.
+    pub fn estimate(snapshot: &Snapshot, options: &WishlistOptions) -> usize {
+        Self::select(snapshot, options)
+            .into_iter()
+            .map(|(weapon, trash)| {
+                if trash {
+                    weapon.wishlist_items(options).count()
+                } else {
+                    weapon.estimate_lines(options)
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the matching weapons in output order, paired with whether they are trash.
+    fn select<'a>(snapshot: &'a Snapshot, options: &WishlistOptions) -> Vec<(&'a Weapon, bool)> {
+        options
+            .query()
+            .run(&snapshot.weapons)
+            .into_iter()
+            .filter_map(|weapon| {
+                // Keeping wins when the cutoffs overlap.
+                if weapon.tier.tier.is_at_least(options.min_tier) {
+                    Some((weapon, false))
+                } else if options.is_trash(weapon.tier.tier) {
+                    Some((weapon, true))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn build(
+        snapshot: &Snapshot,
+        options: &WishlistOptions,
+        generated: DateTime<Utc>,
+    ) -> WishlistDocument {
+        let source = snapshot.date();
+
+        let entries = Self::select(snapshot, options)
+            .into_iter()
+            .flat_map(|(weapon, trash)| {
+                if trash {
+                    weapon.trash_entries(&source, options)
+                } else {
+                    weapon.wishlist_entries(&source, options)
+                }
+            })
+            .collect();
+
+        WishlistDocument {
+            title: Some(format!("PVE Wishlist ({})", options.label())),
+            description: Some(options.description()),
+            header: Self::header(snapshot, options, generated),
+            entries,
+        }
+    }
+
+    /// The snapshot time is when the weapons were read from the sheet. See
+    /// [`WishlistDocument::keep_generated`] to keep the generation time out of diffs.
+    fn header(
+        snapshot: &Snapshot,
+        options: &WishlistOptions,
+        generated: DateTime<Utc>,
+    ) -> Vec<String> {
+        let strictness = match options.strictness {
+            Some(strictness) => format!("{} ({} and above)", strictness, options.min_tier),
+            None => format!("{} and above", options.min_tier),
+        };
+
+        vec![
+            format!(
+                "{}{}",
+                GENERATED_HEADER,
+                generated.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+            format!(
+                "snapshot: {} ({})",
+                snapshot.version(),
+                snapshot.updated.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+            format!("strictness: {}", strictness),
+        ]
+    }
+}
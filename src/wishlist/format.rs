@@ -0,0 +1,181 @@
+use std::collections::{BTreeMap, HashMap};
+use std::{fmt, str::FromStr};
+
+use serde::Serialize;
+
+use super::WishlistDocument;
+use super::parser::WILDCARD_ITEM_HASH;
+
+pub trait WishlistSerializer {
+    fn extension(&self) -> &'static str;
+
+    fn serialize(&self, wishlist: &WishlistDocument) -> String;
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum WishlistFormat {
+    #[default]
+    Dim,
+    LittleLight,
+    Json,
+}
+
+impl WishlistFormat {
+    pub fn serializer(self) -> &'static dyn WishlistSerializer {
+        match self {
+            WishlistFormat::Dim => &DimFormat,
+            WishlistFormat::LittleLight => &LittleLightFormat,
+            WishlistFormat::Json => &JsonFormat,
+        }
+    }
+}
+
+impl FromStr for WishlistFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dim" => Ok(WishlistFormat::Dim),
+            "littlelight" => Ok(WishlistFormat::LittleLight),
+            "json" => Ok(WishlistFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for WishlistFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WishlistFormat::Dim => write!(f, "DIM"),
+            WishlistFormat::LittleLight => write!(f, "Little Light"),
+            WishlistFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+pub struct DimFormat;
+
+impl WishlistSerializer for DimFormat {
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn serialize(&self, wishlist: &WishlistDocument) -> String {
+        wishlist.to_string()
+    }
+}
+
+pub struct LittleLightFormat;
+
+#[derive(Serialize)]
+struct LittleLightWishlist<'a> {
+    name: &'a str,
+    description: &'a str,
+    data: Vec<LittleLightItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct LittleLightItem<'a> {
+    name: &'a str,
+    description: &'a str,
+    hash: u32,
+    plugs: Vec<Vec<u32>>,
+    tags: Vec<&'static str>,
+}
+
+impl WishlistSerializer for LittleLightFormat {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn serialize(&self, wishlist: &WishlistDocument) -> String {
+        let mut data: Vec<LittleLightItem> = Vec::new();
+        let mut index = HashMap::new();
+
+        for entry in &wishlist.entries {
+            // Little Light has no equivalent of DIM's wildcard entries.
+            let Some(hash) = entry.item_hash else {
+                continue;
+            };
+
+            let i = *index.entry((hash, entry.trash)).or_insert_with(|| {
+                data.push(LittleLightItem {
+                    name: entry.comment.as_deref().unwrap_or_default(),
+                    description: entry.notes.as_deref().unwrap_or_default(),
+                    hash,
+                    plugs: Vec::new(),
+                    tags: if entry.trash {
+                        vec!["Trash"]
+                    } else {
+                        vec!["PvE"]
+                    },
+                });
+                data.len() - 1
+            });
+
+            let plugs = &mut data[i].plugs;
+            for (column, perk) in entry.perks.iter().enumerate() {
+                if plugs.len() <= column {
+                    plugs.push(Vec::new());
+                }
+                if !plugs[column].contains(perk) {
+                    plugs[column].push(*perk);
+                }
+            }
+        }
+
+        let wishlist = LittleLightWishlist {
+            name: wishlist.title.as_deref().unwrap_or_default(),
+            description: wishlist.description.as_deref().unwrap_or_default(),
+            data,
+        };
+
+        serde_json::to_string_pretty(&wishlist).unwrap()
+    }
+}
+
+pub struct JsonFormat;
+
+#[derive(Serialize)]
+struct JsonWishlist<'a> {
+    title: Option<&'a str>,
+    description: Option<&'a str>,
+    items: BTreeMap<i64, Vec<JsonRoll<'a>>>,
+}
+
+#[derive(Serialize)]
+struct JsonRoll<'a> {
+    perks: &'a [u32],
+    trash: bool,
+    notes: Option<&'a str>,
+}
+
+impl WishlistSerializer for JsonFormat {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn serialize(&self, wishlist: &WishlistDocument) -> String {
+        let items =
+            wishlist
+                .entries
+                .iter()
+                .fold(BTreeMap::<_, Vec<_>>::new(), |mut items, entry| {
+                    let hash = entry.item_hash.map_or(WILDCARD_ITEM_HASH, i64::from);
+                    items.entry(hash).or_default().push(JsonRoll {
+                        perks: &entry.perks,
+                        trash: entry.trash,
+                        notes: entry.notes.as_deref(),
+                    });
+                    items
+                });
+
+        let wishlist = JsonWishlist {
+            title: wishlist.title.as_deref(),
+            description: wishlist.description.as_deref(),
+            items,
+        };
+
+        serde_json::to_string_pretty(&wishlist).unwrap()
+    }
+}
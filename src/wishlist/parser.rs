@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// DIM's `item=` value for an entry that applies to every item rolling its perks.
+pub const WILDCARD_ITEM_HASH: i64 = -69420;
+
+/// Starts the header comment holding when the wishlist was generated.
+pub const GENERATED_HEADER: &str = "generated: ";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WishlistDocument {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub header: Vec<String>,
+    pub entries: Vec<WishlistEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WishlistEntry {
+    /// `None` for the [`WILDCARD_ITEM_HASH`] entries matching any item.
+    pub item_hash: Option<u32>,
+    pub perks: Vec<u32>,
+    pub trash: bool,
+    pub comment: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl WishlistEntry {
+    fn key(&self) -> (Option<u32>, Vec<u32>, bool) {
+        let mut perks = self.perks.clone();
+        perks.sort_unstable();
+
+        (self.item_hash, perks, self.trash)
+    }
+}
+
+impl WishlistDocument {
+    /// Appends the entries of `other` that are not already present, keeping the first notes seen.
+    pub fn merge(&mut self, other: WishlistDocument) {
+        if self.title.is_none() {
+            self.title = other.title;
+        }
+        if self.description.is_none() {
+            self.description = other.description;
+        }
+
+        self.entries.extend(other.entries);
+        self.dedup();
+    }
+
+    /// Takes the generation time of `previous` when nothing else differs, so republishing an
+    /// unchanged wishlist writes an identical file.
+    pub fn keep_generated(&mut self, previous: &WishlistDocument) {
+        let is_generated = |line: &String| line.starts_with(GENERATED_HEADER);
+        let header = |wishlist: &WishlistDocument| {
+            wishlist
+                .header
+                .iter()
+                .filter(|line| !is_generated(line))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let unchanged = self.title == previous.title
+            && self.description == previous.description
+            && self.entries == previous.entries
+            && header(self) == header(previous);
+        if !unchanged {
+            return;
+        }
+
+        let Some(generated) = previous.header.iter().find(|line| is_generated(line)) else {
+            return;
+        };
+
+        for line in self.header.iter_mut().filter(|line| is_generated(line)) {
+            *line = generated.clone();
+        }
+    }
+
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.entries.retain(|entry| seen.insert(entry.key()));
+    }
+}
+
+/// Parses DIM's wishlist format. Like DIM, lines that are not a title, description, comment
+/// or `dimwishlist:` entry are ignored, as are unknown entry parameters.
+impl FromStr for WishlistDocument {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut wishlist = WishlistDocument::default();
+        let mut in_header = true;
+        let mut comment = None;
+        let mut notes = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                in_header = wishlist.title.is_none() && wishlist.description.is_none();
+                comment = None;
+                notes = None;
+            } else if let Some(title) = line.strip_prefix("title:") {
+                wishlist.title = Some(title.trim().to_string());
+            } else if let Some(description) = line.strip_prefix("description:") {
+                wishlist.description = Some(description.trim().to_string());
+            } else if let Some(block_notes) = line.strip_prefix("//notes:") {
+                notes = Some(block_notes.trim().to_string());
+            } else if let Some(block_comment) = line.strip_prefix("//") {
+                if in_header && (wishlist.title.is_some() || wishlist.description.is_some()) {
+                    wishlist.header.push(block_comment.trim().to_string());
+                } else {
+                    comment = Some(block_comment.trim().to_string());
+                }
+            } else if let Some(entry) = line.strip_prefix("dimwishlist:") {
+                in_header = false;
+                let invalid = || Error::InvalidWishlistLine(i + 1, line.to_string());
+
+                let (entry, inline_notes) = match entry.split_once("#notes:") {
+                    Some((entry, inline_notes)) => (entry, Some(inline_notes.trim().to_string())),
+                    None => (entry, None),
+                };
+
+                let mut item = None;
+                let mut perks = Vec::new();
+                for param in entry.split('&') {
+                    match param.split_once('=') {
+                        Some(("item", value)) => item = Some(value),
+                        Some(("perks", value)) => {
+                            perks = value
+                                .split(',')
+                                .filter(|p| !p.is_empty())
+                                .map(|p| p.parse().map_err(|_| invalid()))
+                                .collect::<Result<_, _>>()?;
+                        }
+                        _ => {}
+                    }
+                }
+
+                let item = item.ok_or_else(invalid)?;
+                let (item_hash, trash) = match item.parse::<i64>().map_err(|_| invalid())? {
+                    WILDCARD_ITEM_HASH => (None, false),
+                    hash => {
+                        let item_hash =
+                            u32::try_from(hash.unsigned_abs()).map_err(|_| invalid())?;
+                        (Some(item_hash), hash < 0)
+                    }
+                };
+
+                wishlist.entries.push(WishlistEntry {
+                    item_hash,
+                    perks,
+                    trash,
+                    comment: comment.clone(),
+                    notes: inline_notes.or_else(|| notes.clone()),
+                });
+            }
+        }
+
+        Ok(wishlist)
+    }
+}
+
+impl fmt::Display for WishlistDocument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(title) = &self.title {
+            writeln!(f, "title: {}", title)?;
+        }
+        if let Some(description) = &self.description {
+            writeln!(f, "description: {}", description)?;
+        }
+        for comment in &self.header {
+            writeln!(f, "// {}", comment)?;
+        }
+
+        let mut block = None;
+        for entry in &self.entries {
+            let current = (&entry.comment, &entry.notes);
+
+            if block != Some(current) {
+                writeln!(f)?;
+                if let Some(comment) = &entry.comment {
+                    writeln!(f, "// {}", comment)?;
+                }
+                if let Some(notes) = &entry.notes {
+                    writeln!(f, "//notes:{}", notes)?;
+                }
+                block = Some(current);
+            }
+
+            let item = match entry.item_hash {
+                None => WILDCARD_ITEM_HASH.to_string(),
+                Some(hash) if entry.trash => format!("-{}", hash),
+                Some(hash) => hash.to_string(),
+            };
+
+            write!(f, "dimwishlist:item={}", item)?;
+            if !entry.perks.is_empty() {
+                let perks = entry
+                    .perks
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "&perks={}", perks.join(","))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
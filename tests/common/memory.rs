@@ -0,0 +1,97 @@
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use endgame_analysis::{DestinyPerk, DestinyPerkManager, DestinyWeapon, DestinyWeaponManager};
+use serde::Deserialize;
+use sqlx::{Database, Pool};
+
+tokio::task_local! {
+    static STORE: Arc<MemoryStore>;
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MemoryStore {
+    pub weapons: Vec<DestinyWeapon>,
+    pub perks: Vec<DestinyPerk>,
+}
+
+impl MemoryStore {
+    /// Reads a JSON file shaped like [`MemoryStore`].
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let json = fs::read_to_string(path).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    /// Runs `f` with [`MemoryManager`] serving from this store.
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        STORE.scope(Arc::new(self), f).await
+    }
+}
+
+/// Serves weapons and perks from the [`MemoryStore`] of the enclosing
+/// [`MemoryStore::scope`] instead of a database.
+///
+/// The manager traits take a pool, so any pool works here; it is never queried.
+pub struct MemoryManager;
+
+#[async_trait]
+impl<Db: Database> DestinyWeaponManager<Db> for MemoryManager {
+    async fn get(_pool: &Pool<Db>, name: &str) -> sqlx::Result<DestinyWeapon> {
+        STORE.with(|store| {
+            store
+                .weapons
+                .iter()
+                .filter(|w| w.name.eq_ignore_ascii_case(name))
+                .min_by_key(|w| w.id)
+                .cloned()
+                .ok_or(sqlx::Error::RowNotFound)
+        })
+    }
+
+    async fn get_by_prefix(_pool: &Pool<Db>, name: &str) -> sqlx::Result<Vec<DestinyWeapon>> {
+        let weapons = STORE.with(|store| {
+            store
+                .weapons
+                .iter()
+                .filter(|w| {
+                    w.name
+                        .get(..name.len())
+                        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(name))
+                })
+                .cloned()
+                .collect()
+        });
+
+        Ok(weapons)
+    }
+}
+
+#[async_trait]
+impl<Db: Database> DestinyPerkManager<Db> for MemoryManager {
+    async fn get(_pool: &Pool<Db>, name: &str) -> sqlx::Result<DestinyPerk> {
+        STORE.with(|store| {
+            store
+                .perks
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+                .cloned()
+                .ok_or(sqlx::Error::RowNotFound)
+        })
+    }
+
+    async fn get_all(_pool: &Pool<Db>, names: &[String]) -> sqlx::Result<Vec<DestinyPerk>> {
+        let perks = STORE.with(|store| {
+            store
+                .perks
+                .iter()
+                .filter(|p| names.iter().any(|name| p.name.eq_ignore_ascii_case(name)))
+                .cloned()
+                .collect()
+        });
+
+        Ok(perks)
+    }
+}
@@ -0,0 +1,75 @@
+#![allow(dead_code)]
+
+mod memory;
+
+use std::fs;
+
+use chrono::{TimeZone, Utc};
+use endgame_analysis::endgame_analysis::{Ammo, Archetype, Snapshot, WeaponBuilder};
+use google_sheets_api::types::sheet::RowData;
+use sqlx::{Sqlite, SqlitePool};
+
+pub use memory::{MemoryManager, MemoryStore};
+
+pub const MANIFEST: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/manifest.json");
+pub const SHOTGUNS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/shotguns.json");
+
+/// Rows for the `destiny_weapons` table of the database manager tests.
+pub const WEAPONS: [(i64, &str, &[i64], &[i64]); 5] = [
+    (100, "Heritage", &[1, 2], &[4, 5]),
+    (101, "Heritage (Adept)", &[1, 2], &[4, 5]),
+    (102, "Heritage", &[1], &[4]),
+    (300, "IKELOS_SMG_v1.0.3", &[], &[]),
+    (301, "IKELOSXSMG", &[], &[]),
+];
+
+/// Rows for the `destiny_perks` table of the database manager tests.
+pub const PERKS: [(i64, &str); 3] = [(1, "Slideshot"), (2, "Threat Detector"), (11, "Slideshot")];
+
+pub fn fixture(path: &str) -> String {
+    fs::read_to_string(path).unwrap()
+}
+
+/// The header row followed by the weapon rows of the shotgun tab.
+pub fn rows() -> (RowData, Vec<RowData>) {
+    let mut rows = serde_json::from_str::<Vec<RowData>>(&fixture(SHOTGUNS)).unwrap();
+    let header = rows.remove(0);
+
+    (header, rows)
+}
+
+pub fn pool() -> SqlitePool {
+    SqlitePool::connect_lazy("sqlite::memory:").unwrap()
+}
+
+pub async fn snapshot() -> Snapshot {
+    let pool = pool();
+    let (header, rows) = rows();
+
+    let weapons = MemoryStore::load(MANIFEST)
+        .scope(async {
+            let mut weapons = Vec::new();
+            for row in rows {
+                let Some(builder) =
+                    WeaponBuilder::from_row_data(Archetype::Shotgun, &header, row).unwrap()
+                else {
+                    continue;
+                };
+
+                let (weapon, _) = builder
+                    .ammo(Ammo::Special)
+                    .build::<Sqlite, MemoryManager, MemoryManager>(&pool)
+                    .await
+                    .unwrap();
+                weapons.push(weapon);
+            }
+
+            weapons
+        })
+        .await;
+
+    Snapshot {
+        updated: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+        weapons,
+    }
+}
@@ -0,0 +1,107 @@
+use endgame_analysis::endgame_analysis::{Ammo, EndgameConfig, TabColour};
+use google_sheets_api::types::common::Color;
+use serde_json::json;
+
+fn colour(red: f64, green: f64, blue: f64) -> Color {
+    serde_json::from_value(json!({ "red": red, "green": green, "blue": blue })).unwrap()
+}
+
+#[test]
+fn matches_tab_colours_within_tolerance() {
+    let config = EndgameConfig::default();
+
+    assert_eq!(
+        config.ammo(&colour(0.9529412, 0.9529412, 0.9529412)),
+        Some(Ammo::Primary)
+    );
+    assert_eq!(config.ammo(&colour(0.0, 1.0, 0.0)), Some(Ammo::Special));
+    assert_eq!(config.ammo(&colour(0.6, 0.0, 1.0)), Some(Ammo::Heavy));
+    // 0x9A01FE, one step off on every channel.
+    assert_eq!(
+        config.ammo(&colour(0.6039216, 0.0039216, 0.9960784)),
+        Some(Ammo::Heavy)
+    );
+    assert_eq!(config.ammo(&colour(1.0, 1.0, 1.0)), None);
+
+    let strict = EndgameConfig {
+        colour_tolerance: 0,
+        ..EndgameConfig::default()
+    };
+    assert_eq!(strict.ammo(&colour(0.6039216, 0.0039216, 0.9960784)), None);
+}
+
+#[test]
+fn filters_tabs() {
+    let config = EndgameConfig {
+        include_tabs: vec![String::from("Shotguns"), String::from("Snipers")],
+        exclude_tabs: vec![String::from("snipers")],
+        ..EndgameConfig::default()
+    };
+
+    assert!(config.includes_tab("Shotguns"));
+    assert!(config.includes_tab(" shotguns "));
+    assert!(!config.includes_tab("Snipers"));
+    assert!(!config.includes_tab("Rocket Launchers"));
+
+    assert!(EndgameConfig::default().includes_tab("Rocket Launchers"));
+}
+
+#[test]
+fn parses_toml() {
+    let config = EndgameConfig::from_toml(
+        r#"
+        spreadsheet_id = "fork"
+        exclude_tabs = ["Glaives"]
+        colour_tolerance = 8
+        snapshot_path = "fork.json"
+
+        [[tab_colours]]
+        colour = 0xFF0000
+        ammo = "Heavy"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.spreadsheet_id, "fork");
+    assert_eq!(config.api_key, None);
+    assert_eq!(config.exclude_tabs, vec!["Glaives"]);
+    assert_eq!(config.colour_tolerance, 8);
+    assert_eq!(config.snapshot_path.to_str(), Some("fork.json"));
+    assert_eq!(
+        config.tab_colours,
+        vec![TabColour {
+            colour: 0xFF0000,
+            ammo: Ammo::Heavy
+        }]
+    );
+    assert_eq!(config.ammo(&colour(1.0, 0.0, 0.0)), Some(Ammo::Heavy));
+
+    assert!(EndgameConfig::from_toml("colour_tolerance = 300").is_err());
+    assert_eq!(
+        EndgameConfig::from_toml("").unwrap(),
+        EndgameConfig::default()
+    );
+}
+
+#[test]
+fn applies_env_overrides_to_config_files() {
+    let path = std::env::temp_dir().join(format!("endgame-analysis-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "spreadsheet_id = \"fork\"\nsnapshot_path = \"fork.json\"",
+    )
+    .unwrap();
+
+    // The only test in this binary touching the environment.
+    unsafe {
+        std::env::set_var("GOOGLE_API_KEY", "env-key");
+        std::env::set_var("ENDGAME_ANALYSIS_SNAPSHOT", "env.json");
+    }
+
+    let config = EndgameConfig::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(config.spreadsheet_id, "fork");
+    assert_eq!(config.api_key().unwrap(), "env-key");
+    assert_eq!(config.snapshot_path.to_str(), Some("env.json"));
+}
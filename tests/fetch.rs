@@ -0,0 +1,262 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use endgame_analysis::Error;
+use endgame_analysis::endgame_analysis::fetch::parse_retry_after;
+use endgame_analysis::endgame_analysis::{EndgameConfig, RetryPolicy, SheetsFetcher};
+use endgame_analysis::error::SheetsError;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A stand-in for the Sheets API answering each request with the next scripted response.
+/// Requests past the end of the script never get an answer.
+struct MockSheets {
+    addr: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockSheets {
+    async fn start(responses: Vec<&'static str>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let log = requests.clone();
+        tokio::spawn(async move {
+            let mut responses = responses.into_iter();
+
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let request = String::from_utf8_lossy(&request);
+                let line = request.lines().next().unwrap_or_default().to_string();
+                log.lock().unwrap().push(line);
+
+                match responses.next() {
+                    Some(response) => {
+                        stream.write_all(response.as_bytes()).await.unwrap();
+                        stream.shutdown().await.unwrap();
+                    }
+                    None => {
+                        tokio::spawn(async move {
+                            tokio::time::sleep(Duration::from_secs(60)).await;
+                            drop(stream);
+                        });
+                    }
+                }
+            }
+        });
+
+        Self { addr, requests }
+    }
+
+    fn config(&self, retry: RetryPolicy) -> EndgameConfig {
+        EndgameConfig {
+            api_url: self.addr.clone(),
+            api_key: Some(String::from("test-key")),
+            retry,
+            ..EndgameConfig::default()
+        }
+    }
+
+    fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn response(status: &str, headers: &str, body: &str) -> &'static str {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+        status,
+        body.len(),
+        headers,
+        body
+    );
+
+    Box::leak(response.into_boxed_str())
+}
+
+fn fast_retries() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        base_delay_ms: 10,
+        max_delay_ms: 50,
+        attempt_timeout_ms: 1_000,
+        timeout_ms: 5_000,
+    }
+}
+
+#[tokio::test]
+async fn retries_transient_errors() {
+    let server = MockSheets::start(vec![
+        response("503 Service Unavailable", "", "{}"),
+        response("429 Too Many Requests", "Retry-After: 0\r\n", "{}"),
+        response("200 OK", "", r#"{ "sheets": [] }"#),
+    ])
+    .await;
+
+    let sheets = SheetsFetcher::new(&server.config(fast_retries()))
+        .unwrap()
+        .fetch("sheet-id")
+        .await
+        .unwrap();
+    assert!(sheets.is_empty());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(
+        requests[0].starts_with("GET /v4/spreadsheets/sheet-id?includeGridData=true&key=test-key ")
+    );
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = MockSheets::start(vec![response(
+        "403 Forbidden",
+        "",
+        r#"{ "error": { "code": 403, "message": "API key not valid" } }"#,
+    )])
+    .await;
+
+    let result = SheetsFetcher::new(&server.config(fast_retries()))
+        .unwrap()
+        .fetch("sheet-id")
+        .await;
+
+    match result {
+        Err(Error::Sheets(SheetsError::Status {
+            status, message, ..
+        })) => {
+            assert_eq!(status, 403);
+            assert_eq!(message, "API key not valid");
+        }
+        other => panic!("expected a 403, got {:?}", other.err()),
+    }
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let server = MockSheets::start(vec![
+        response("500 Internal Server Error", "", "oops"),
+        response("500 Internal Server Error", "", "oops"),
+        response("500 Internal Server Error", "", "oops"),
+        response("500 Internal Server Error", "", "oops"),
+    ])
+    .await;
+
+    let result = SheetsFetcher::new(&server.config(fast_retries()))
+        .unwrap()
+        .fetch("sheet-id")
+        .await;
+
+    match result {
+        Err(Error::Sheets(e)) => {
+            assert!(e.is_retryable());
+            assert_eq!(e.to_string(), "HTTP 500: oops");
+        }
+        other => panic!("expected a 500, got {:?}", other.err()),
+    }
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn rejects_invalid_responses() {
+    let server = MockSheets::start(vec![response("200 OK", "", "<html></html>")]).await;
+
+    let result = SheetsFetcher::new(&server.config(fast_retries()))
+        .unwrap()
+        .fetch("sheet-id")
+        .await;
+
+    assert!(matches!(result, Err(Error::Sheets(SheetsError::Decode(_)))));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn times_out_unresponsive_servers() {
+    let server = MockSheets::start(Vec::new()).await;
+
+    let retry = RetryPolicy {
+        max_attempts: 10,
+        attempt_timeout_ms: 100,
+        timeout_ms: 300,
+        ..fast_retries()
+    };
+
+    let result = SheetsFetcher::new(&server.config(retry))
+        .unwrap()
+        .fetch("sheet-id")
+        .await;
+
+    assert!(matches!(result, Err(Error::Sheets(SheetsError::Timeout))));
+    assert!(server.requests().len() > 1);
+}
+
+#[test]
+fn backs_off_exponentially() {
+    let policy = RetryPolicy {
+        base_delay_ms: 100,
+        max_delay_ms: 1_000,
+        ..RetryPolicy::default()
+    };
+
+    for (attempt, backoff) in [(1, 100), (2, 200), (3, 400), (4, 800), (5, 1_000)] {
+        let delay = policy.delay(attempt, None);
+        assert!(delay >= Duration::from_millis(backoff / 2), "{:?}", delay);
+        assert!(delay <= Duration::from_millis(backoff), "{:?}", delay);
+    }
+
+    assert_eq!(
+        policy.delay(1, Some(Duration::from_millis(700))),
+        Duration::from_millis(700)
+    );
+    // Retry-After is a floor, not capped by max_delay_ms.
+    assert_eq!(
+        policy.delay(1, Some(Duration::from_secs(60))),
+        Duration::from_secs(60)
+    );
+}
+
+#[test]
+fn jitters_delays() {
+    let policy = RetryPolicy {
+        base_delay_ms: 1_000_000,
+        ..RetryPolicy::default()
+    };
+
+    let delays = (0..8)
+        .map(|_| policy.delay(1, None))
+        .collect::<std::collections::HashSet<_>>();
+    assert!(delays.len() > 1);
+}
+
+#[test]
+fn parses_retry_after() {
+    let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+    assert_eq!(
+        parse_retry_after("120", now),
+        Some(Duration::from_secs(120))
+    );
+    assert_eq!(
+        parse_retry_after("Wed, 01 Jan 2025 00:00:30 GMT", now),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(
+        parse_retry_after("Tue, 31 Dec 2024 23:59:00 GMT", now),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(parse_retry_after("soon", now), None);
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use endgame_analysis::endgame_analysis::Stat;
+use endgame_analysis::managers::ManifestManager;
+use endgame_analysis::{DestinyPerkManager, DestinyWeaponManager};
+use sqlx::{Sqlite, SqlitePool};
+
+const WORLD_CONTENT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/world_content.json"
+);
+
+fn pool() -> SqlitePool {
+    SqlitePool::connect_lazy("sqlite::memory:").unwrap()
+}
+
+#[tokio::test]
+async fn classifies_sockets_by_plug_category() {
+    let pool = pool();
+    let manifest = Arc::new(ManifestManager::load(WORLD_CONTENT).unwrap());
+
+    let weapon = manifest
+        .scope(<ManifestManager as DestinyWeaponManager<Sqlite>>::get(
+            &pool, "heritage",
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(weapon.id, 1000);
+    assert_eq!(weapon.icon, "/icons/heritage.jpg");
+    // The origin trait and tracker sockets are skipped, whatever their position.
+    assert_eq!(weapon.column_1, vec![3200]);
+    assert_eq!(weapon.column_2, vec![3100, 3101]);
+    assert_eq!(weapon.perk_1, vec![1, 2]);
+    assert_eq!(weapon.perk_2, vec![4, 5]);
+    assert_eq!(weapon.stats.get(Stat::Impact), Some(80));
+}
+
+#[tokio::test]
+async fn serves_lookups_from_the_scoped_manifest() {
+    let pool = pool();
+    let manifest = Arc::new(ManifestManager::load(WORLD_CONTENT).unwrap());
+
+    let (ids, perk) = manifest
+        .scope(async {
+            let ids = ManifestManager::get_by_prefix(&pool, "Heritage")
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|w| w.id)
+                .collect::<Vec<_>>();
+            let perk = <ManifestManager as DestinyPerkManager<Sqlite>>::get(&pool, "SLIDESHOT")
+                .await
+                .unwrap();
+
+            (ids, perk)
+        })
+        .await;
+
+    assert_eq!(ids, vec![1000, 1001]);
+    assert_eq!(perk.id, 1);
+    assert_eq!(perk.description, "Sliding partially reloads the magazine.");
+
+    let outside = <ManifestManager as DestinyWeaponManager<Sqlite>>::get(&pool, "Heritage").await;
+    assert!(matches!(outside, Err(sqlx::Error::Configuration(_))));
+}
@@ -0,0 +1,332 @@
+mod common;
+
+use common::{MemoryManager, MemoryStore};
+use endgame_analysis::endgame_analysis::{
+    Archetype, RefreshIssue, Snapshot, Tier, TierLabel, Variant, Weapon, WeaponBuilder, WeaponStats,
+};
+use endgame_analysis::{DestinyPerk, DestinyWeapon, Error};
+use google_sheets_api::types::sheet::CellData;
+use serde_json::json;
+use sqlx::Sqlite;
+
+fn tier_cell(tier: &str, red: f64, green: f64, blue: f64) -> CellData {
+    serde_json::from_value(json!({
+        "formattedValue": tier,
+        "effectiveFormat": {
+            "backgroundColorStyle": {
+                "rgbColor": { "red": red, "green": green, "blue": blue }
+            }
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn parses_weapon_rows() {
+    let (header, mut rows) = common::rows();
+
+    let heritage = WeaponBuilder::from_row_data(Archetype::Shotgun, &header, rows.remove(0))
+        .unwrap()
+        .unwrap();
+    assert_eq!(heritage.name, "Heritage");
+    assert_eq!(heritage.archetype, Archetype::Shotgun);
+    assert_eq!(heritage.affinity, "Kinetic");
+    assert_eq!(heritage.frame.as_deref(), Some("Slug"));
+    assert!(heritage.enhanceable);
+    assert_eq!(heritage.reserves, Some(21));
+    assert_eq!(heritage.column_1, "Slideshot\nThreat Detector");
+    assert_eq!(heritage.column_2, "Recombination\nTrench Barrel");
+    assert_eq!(heritage.origin_trait, "Nadir Focus");
+    assert_eq!(heritage.rank, 1);
+    assert_eq!(heritage.tier.tier, TierLabel::S);
+
+    let matador = WeaponBuilder::from_row_data(Archetype::Shotgun, &header, rows.remove(0))
+        .unwrap()
+        .unwrap();
+    assert!(!matador.enhanceable);
+    assert_eq!(matador.reserves, None);
+    assert_eq!(matador.tier.tier, TierLabel::B);
+
+    let ideal = WeaponBuilder::from_row_data(Archetype::Shotgun, &header, rows.remove(0)).unwrap();
+    assert!(ideal.is_none());
+}
+
+#[test]
+fn rejects_malformed_rows() {
+    let (header, mut rows) = common::rows();
+
+    let mut row = rows.remove(0);
+    row.values[8].formatted_value = Some(String::from("?"));
+    match WeaponBuilder::from_row_data(Archetype::Shotgun, &header, row) {
+        Err(Error::Parse(e)) => assert_eq!(e, "rank '?' for Heritage"),
+        other => panic!("expected a parse error, got {:?}", other.err()),
+    }
+
+    let mut row = rows.remove(0);
+    row.values.truncate(5);
+    assert!(WeaponBuilder::from_row_data(Archetype::Shotgun, &header, row).is_err());
+
+    assert!(Tier::try_from(tier_cell("Q", 1.0, 1.0, 1.0)).is_err());
+}
+
+#[test]
+fn normalizes_weapon_names() {
+    let cases = [
+        ("Song of Ir Yut", "Song of Ir Yût"),
+        ("Braytech Osprey", "BrayTech Osprey"),
+        ("IKELOS_SMG_V1.0.3", "IKELOS_SMG_v1.0.3"),
+        ("Judgement", "Judgment"),
+        ("  Heritage ", "Heritage"),
+    ];
+
+    for (sheet, manifest) in cases {
+        let builder = WeaponBuilder::new(sheet, Archetype::Shotgun);
+        assert_eq!(builder.name, manifest);
+        assert_eq!(builder.variant, Variant::Base);
+    }
+
+    let brave = WeaponBuilder::new("Elsie's Rifle\nBRAVE version", Archetype::PulseRifle);
+    assert_eq!(brave.name, "Elsie's Rifle (Brave)");
+    assert_eq!(brave.base_name(), "Elsie's Rifle");
+    assert_eq!(brave.variant, Variant::Brave);
+
+    let brave = WeaponBuilder::new("Elsie's Rifle", Archetype::PulseRifle);
+    assert_eq!(brave.name, "Elsie's Rifle (Brave)");
+    assert_eq!(brave.base_name(), "Elsie's Rifle");
+    assert_eq!(brave.variant, Variant::Brave);
+
+    let brave = WeaponBuilder::new(
+        "Edge Transit (BRAVE version)",
+        Archetype::HeavyGrenadeLauncher,
+    );
+    assert_eq!(brave.name, "Edge Transit (Brave)");
+    assert_eq!(brave.variant, Variant::Brave);
+}
+
+#[tokio::test]
+async fn splits_brave_items_from_the_base_row() {
+    let weapon = |id, name: &str| DestinyWeapon {
+        id,
+        icon: String::new(),
+        name: String::from(name),
+        column_1: Vec::new(),
+        column_2: Vec::new(),
+        perk_1: vec![1],
+        perk_2: vec![2],
+        stats: WeaponStats::default(),
+    };
+    let perk = |id, name: &str| DestinyPerk {
+        id,
+        name: String::from(name),
+        description: String::new(),
+    };
+    let store = MemoryStore {
+        weapons: vec![
+            weapon(10, "Edge Transit"),
+            // Cannot roll the recommended perks, so it gets no wishlist item.
+            DestinyWeapon {
+                perk_1: vec![9],
+                ..weapon(11, "Edge Transit (Adept)")
+            },
+            weapon(12, "Edge Transit (Brave)"),
+        ],
+        perks: vec![perk(1, "Quick Launch"), perk(2, "Spike Grenades")],
+    };
+
+    let pool = common::pool();
+    let build = |name| {
+        WeaponBuilder::new(name, Archetype::HeavyGrenadeLauncher)
+            .affinity("Void")
+            .column_1("Quick Launch")
+            .column_2("Spike Grenades")
+            .build::<Sqlite, MemoryManager, MemoryManager>(&pool)
+    };
+
+    let ((base, base_issues), (brave, _)) = store
+        .scope(async {
+            (
+                build("Edge Transit").await.unwrap(),
+                build("Edge Transit\nBRAVE version").await.unwrap(),
+            )
+        })
+        .await;
+
+    let hashes = |weapon: &Weapon| weapon.items.iter().map(|i| i.hash).collect::<Vec<_>>();
+    assert_eq!(base.name(), "Edge Transit");
+    assert_eq!(hashes(&base), vec![10]);
+    assert_eq!(base_issues, vec![RefreshIssue::NoPerkCombination(11)]);
+    assert_eq!(brave.name(), "Edge Transit (Brave)");
+    assert_eq!(hashes(&brave), vec![12]);
+    assert_eq!(brave.variants(), vec![Variant::Brave]);
+
+    let old = Snapshot::new(vec![base]);
+    let new = Snapshot::new(vec![brave]);
+    let diff = old.diff(&new);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.removed.len(), 1);
+}
+
+#[tokio::test]
+async fn prefers_rollable_items_as_the_base() {
+    let weapon = |id, perk_1: Vec<i64>, perk_2: Vec<i64>| DestinyWeapon {
+        id,
+        icon: String::new(),
+        name: String::from("Edge Transit"),
+        column_1: Vec::new(),
+        column_2: Vec::new(),
+        perk_1,
+        perk_2,
+        stats: WeaponStats::default(),
+    };
+    let store = MemoryStore {
+        // The sunset original has the lower hash but no plugs that can still roll.
+        weapons: vec![
+            weapon(5, Vec::new(), Vec::new()),
+            weapon(10, vec![1], vec![2]),
+        ],
+        perks: Vec::new(),
+    };
+
+    let pool = common::pool();
+    let variants = store
+        .scope(Variant::resolve::<Sqlite, MemoryManager>(
+            &pool,
+            "Edge Transit",
+        ))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(variant, w)| (variant, w.id))
+        .collect::<Vec<_>>();
+
+    assert_eq!(variants, vec![(Variant::Base, 10), (Variant::Duplicate, 5)]);
+}
+
+#[tokio::test]
+async fn reports_what_a_build_could_not_resolve() {
+    let pool = common::pool();
+
+    let (weapon, issues) = MemoryStore::load(common::MANIFEST)
+        .scope(
+            WeaponBuilder::new("Heritage", Archetype::Shotgun)
+                .affinity("Kinetic")
+                .column_1("Slideshot\nNot A Perk\nField Prep")
+                .column_2("Trench Barrel")
+                .build::<Sqlite, MemoryManager, MemoryManager>(&pool),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(weapon.items.len(), 2);
+    assert_eq!(
+        issues,
+        vec![
+            RefreshIssue::UnresolvedPerks(vec![String::from("Not A Perk")]),
+            RefreshIssue::PerksNotInPool(vec![String::from("Field Prep")]),
+        ]
+    );
+}
+
+#[test]
+fn converts_tier_colours() {
+    let s = Tier::try_from(tier_cell("S", 1.0, 0.49803922, 0.49803922)).unwrap();
+    assert_eq!(s.tier, TierLabel::S);
+    assert_eq!(s.colour, 0xFF7F7F);
+
+    let b = Tier::try_from(tier_cell("B", 1.0, 1.0, 0.49803922)).unwrap();
+    assert_eq!(b.tier, TierLabel::B);
+    assert_eq!(b.colour, 0xFFFF7F);
+
+    let black = Tier::try_from(tier_cell("F", 0.0, 0.0, 0.0)).unwrap();
+    assert_eq!(black.colour, 0x000000);
+
+    let clamped = Tier::try_from(tier_cell("F", 1.5, -0.5, 0.0)).unwrap();
+    assert_eq!(clamped.colour, 0xFF0000);
+}
+
+#[test]
+fn parses_sheet_archetypes() {
+    assert_eq!("HCs".parse::<Archetype>(), Ok(Archetype::HandCannon));
+    assert_eq!(
+        "BGLs".parse::<Archetype>(),
+        Ok(Archetype::BreechGrenadeLauncher)
+    );
+    assert_eq!(
+        "HGLs".parse::<Archetype>(),
+        Ok(Archetype::HeavyGrenadeLauncher)
+    );
+    assert_eq!(
+        "LFRs".parse::<Archetype>(),
+        Ok(Archetype::LinearFusionRifle)
+    );
+    assert_eq!("shotgun".parse::<Archetype>(), Ok(Archetype::Shotgun));
+    assert_eq!("Grenades".parse::<Archetype>(), Err(()));
+}
+
+#[tokio::test]
+async fn resolves_perk_columns() {
+    let snapshot = common::snapshot().await;
+    let heritage = &snapshot.weapons[0];
+
+    let [column_1, column_2] = heritage.perks();
+
+    let slideshot = &column_1[0];
+    assert_eq!(slideshot.name, "Slideshot");
+    assert_eq!(slideshot.priority, 0);
+    assert_eq!(slideshot.hashes, vec![1, 8]);
+    assert!(slideshot.enhanced);
+    assert_eq!(
+        slideshot.description,
+        "Sliding partially reloads the magazine."
+    );
+
+    let threat_detector = &column_1[1];
+    assert_eq!(threat_detector.priority, 1);
+    assert_eq!(threat_detector.hashes, vec![2]);
+    assert!(!threat_detector.enhanced);
+
+    let names = column_2
+        .iter()
+        .map(|perk| perk.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Recombination", "Trench Barrel"]);
+}
+
+#[tokio::test]
+async fn reads_snapshots_without_resolved_perks() {
+    let mut legacy = serde_json::to_value(common::snapshot().await).unwrap();
+    for weapon in legacy["weapons"].as_array_mut().unwrap() {
+        let weapon = weapon.as_object_mut().unwrap();
+        weapon.remove("perks");
+        weapon.insert(
+            String::from("column_1"),
+            json!("Slideshot\nThreat Detector"),
+        );
+        weapon.insert(String::from("column_2"), json!("Recombination"));
+    }
+
+    let path = std::env::temp_dir().join(format!("legacy-snapshot-{}.json", std::process::id()));
+    std::fs::write(&path, legacy.to_string()).unwrap();
+    let snapshot = Snapshot::read_from(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let [column_1, column_2] = snapshot.weapons[0].perks();
+    let names = column_1.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["Slideshot", "Threat Detector"]);
+    assert_eq!(column_1[1].priority, 1);
+    assert!(column_1[0].hashes.is_empty());
+    assert_eq!(column_2[0].name, "Recombination");
+}
+
+#[test]
+fn rejects_unversioned_snapshots() {
+    let path = std::env::temp_dir().join(format!("bare-snapshot-{}.json", std::process::id()));
+    std::fs::write(&path, r#"[{"name": "Heritage", "archetype": "Shotgun"}]"#).unwrap();
+    let snapshot = Snapshot::read_from(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    match snapshot {
+        Err(Error::Parse(e)) => assert!(e.contains("predates versioned snapshots")),
+        other => panic!("expected a parse error, got {:?}", other.err()),
+    }
+}
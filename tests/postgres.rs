@@ -0,0 +1,143 @@
+mod common;
+
+use std::str::FromStr;
+use std::{env, process};
+
+use endgame_analysis::endgame_analysis::Variant;
+use endgame_analysis::managers::PostgresManager;
+use endgame_analysis::{DestinyPerkManager, DestinyWeaponManager};
+use sqlx::postgres::PgConnectOptions;
+use sqlx::{PgPool, Postgres};
+
+use common::{PERKS, WEAPONS};
+
+/// A schema of its own in the database at `DATABASE_URL`, dropped on close.
+///
+/// The tests are ignored by default, run them with `cargo test --features postgres -- --ignored`.
+struct Database {
+    pool: PgPool,
+    admin: PgPool,
+    schema: String,
+}
+
+impl Database {
+    async fn new(name: &str) -> Self {
+        let url = env::var("DATABASE_URL").expect("DATABASE_URL must point at a Postgres server");
+
+        let schema = format!("endgame_analysis_{}_{}", name, process::id());
+
+        let admin = PgPool::connect(&url).await.unwrap();
+        sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", schema))
+            .execute(&admin)
+            .await
+            .unwrap();
+        sqlx::query(&format!("CREATE SCHEMA {}", schema))
+            .execute(&admin)
+            .await
+            .unwrap();
+
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPool::connect_with(options).await.unwrap();
+
+        PostgresManager::migrate(&pool).await.unwrap();
+
+        for (id, name, perk_1, perk_2) in WEAPONS {
+            sqlx::query(
+                "INSERT INTO destiny_weapons (id, name, perk_1, perk_2) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(id)
+            .bind(name)
+            .bind(perk_1)
+            .bind(perk_2)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        for (id, name) in PERKS {
+            sqlx::query("INSERT INTO destiny_perks (id, name) VALUES ($1, $2)")
+                .bind(id)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        Self {
+            pool,
+            admin,
+            schema,
+        }
+    }
+
+    async fn close(self) {
+        self.pool.close().await;
+        sqlx::query(&format!("DROP SCHEMA {} CASCADE", self.schema))
+            .execute(&self.admin)
+            .await
+            .unwrap();
+        self.admin.close().await;
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs DATABASE_URL"]
+async fn matches_weapon_names_ignoring_case() {
+    let db = Database::new("case").await;
+
+    let weapon = <PostgresManager as DestinyWeaponManager<Postgres>>::get(&db.pool, "HERITAGE")
+        .await
+        .unwrap();
+    assert_eq!(weapon.id, 100);
+    assert_eq!(weapon.perk_1, vec![1, 2]);
+
+    let ids = PostgresManager::get_by_prefix(&db.pool, "heritage")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|w| w.id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![100, 101, 102]);
+
+    let variants = Variant::resolve::<Postgres, PostgresManager>(&db.pool, "heritage")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(variant, w)| (variant, w.id))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variants,
+        vec![
+            (Variant::Base, 100),
+            (Variant::Duplicate, 102),
+            (Variant::Adept, 101)
+        ]
+    );
+
+    db.close().await;
+}
+
+#[tokio::test]
+#[ignore = "needs DATABASE_URL"]
+async fn gets_perks_ignoring_case() {
+    let db = Database::new("perks").await;
+
+    let perk = <PostgresManager as DestinyPerkManager<Postgres>>::get(&db.pool, "threat detector")
+        .await
+        .unwrap();
+    assert_eq!(perk.id, 2);
+
+    let names = [String::from("SLIDESHOT")];
+    let mut ids = PostgresManager::get_all(&db.pool, &names)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, vec![1, 11]);
+
+    db.close().await;
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use endgame_analysis::endgame_analysis::{SortKey, Stat, TierLabel, Weapon, WeaponQuery};
+
+fn by_impact(weapons: &[Weapon]) -> Vec<&str> {
+    WeaponQuery::new()
+        .sort(SortKey::Stat(Stat::Impact))
+        .run(weapons)
+        .into_iter()
+        .map(|weapon| weapon.name())
+        .collect()
+}
+
+#[tokio::test]
+async fn sorts_by_weapon_stats() {
+    let mut snapshot = common::snapshot().await;
+
+    let heritage = &snapshot.weapons[0];
+    assert_eq!(heritage.stats.get(Stat::Impact), Some(80));
+    assert_eq!(heritage.stats.rounds_per_minute, Some(55));
+
+    // Tiers come first, even though Matador 64 has the higher impact.
+    assert_eq!(by_impact(&snapshot.weapons), vec!["Heritage", "Matador 64"]);
+
+    snapshot.weapons[1].tier.tier = TierLabel::S;
+    assert_eq!(by_impact(&snapshot.weapons), vec!["Matador 64", "Heritage"]);
+
+    assert_eq!("rpm".parse::<Stat>(), Ok(Stat::RoundsPerMinute));
+    assert_eq!("Reload Speed".parse::<Stat>(), Ok(Stat::ReloadSpeed));
+}
+
+#[tokio::test]
+async fn excludes_unranked_weapons_from_tier_ranges() {
+    let mut snapshot = common::snapshot().await;
+    snapshot.weapons[1].tier.tier = TierLabel::None;
+
+    let query = WeaponQuery {
+        best_tier: Some(TierLabel::A),
+        ..WeaponQuery::new()
+    };
+    assert!(query.run(&snapshot.weapons).is_empty());
+
+    let names = WeaponQuery::new()
+        .tier_range(TierLabel::S, TierLabel::F)
+        .run(&snapshot.weapons)
+        .into_iter()
+        .map(|weapon| weapon.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Heritage"]);
+}
+
+#[tokio::test]
+async fn limits_stat_sorts_after_ranking_tiers() {
+    let snapshot = common::snapshot().await;
+
+    let names = WeaponQuery::new()
+        .sort(SortKey::Stat(Stat::Impact))
+        .limit(Some(1))
+        .run(&snapshot.weapons)
+        .into_iter()
+        .map(|weapon| weapon.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Heritage"]);
+}
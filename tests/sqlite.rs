@@ -0,0 +1,181 @@
+mod common;
+
+use std::path::PathBuf;
+use std::{env, fs, process};
+
+use endgame_analysis::endgame_analysis::Variant;
+use endgame_analysis::managers::SqliteManager;
+use endgame_analysis::{DestinyPerkManager, DestinyWeaponManager};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Sqlite, SqlitePool};
+
+use common::{PERKS, WEAPONS};
+
+struct Database {
+    pool: SqlitePool,
+    path: PathBuf,
+}
+
+impl Database {
+    async fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("endgame-analysis-{}-{}.db", name, process::id()));
+        let _ = fs::remove_file(&path);
+
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await.unwrap();
+
+        SqliteManager::migrate(&pool).await.unwrap();
+
+        for (id, name, perk_1, perk_2) in WEAPONS {
+            sqlx::query(
+                "INSERT INTO destiny_weapons (id, icon, name, perk_1, perk_2) VALUES (?, '', ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(name)
+            .bind(serde_json::to_string(perk_1).unwrap())
+            .bind(serde_json::to_string(perk_2).unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        for (id, name) in PERKS {
+            sqlx::query("INSERT INTO destiny_perks (id, name) VALUES (?, ?)")
+                .bind(id)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        Self { pool, path }
+    }
+
+    async fn close(self) {
+        self.pool.close().await;
+        let _ = fs::remove_file(self.path);
+    }
+}
+
+#[tokio::test]
+async fn migrations_are_idempotent() {
+    let db = Database::new("migrations").await;
+
+    SqliteManager::migrate(&db.pool).await.unwrap();
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn gets_weapons_by_name() {
+    let db = Database::new("weapons").await;
+
+    let weapon = <SqliteManager as DestinyWeaponManager<Sqlite>>::get(&db.pool, "Heritage")
+        .await
+        .unwrap();
+    assert_eq!(weapon.id, 100);
+    assert_eq!(weapon.perk_1, vec![1, 2]);
+    assert_eq!(weapon.perk_2, vec![4, 5]);
+    assert!(weapon.column_1.is_empty());
+
+    let missing = <SqliteManager as DestinyWeaponManager<Sqlite>>::get(&db.pool, "Heritag").await;
+    assert!(matches!(missing, Err(sqlx::Error::RowNotFound)));
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn gets_weapons_by_prefix() {
+    let db = Database::new("prefix").await;
+
+    let ids = |weapons: Vec<endgame_analysis::DestinyWeapon>| {
+        weapons.into_iter().map(|w| w.id).collect::<Vec<_>>()
+    };
+
+    let heritage = SqliteManager::get_by_prefix(&db.pool, "heritage")
+        .await
+        .unwrap();
+    assert_eq!(ids(heritage), vec![100, 101, 102]);
+
+    // `_` is a LIKE wildcard and must be matched literally.
+    let ikelos = SqliteManager::get_by_prefix(&db.pool, "IKELOS_SMG")
+        .await
+        .unwrap();
+    assert_eq!(ids(ikelos), vec![300]);
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn matches_weapon_names_ignoring_case() {
+    let db = Database::new("case").await;
+
+    let weapon = <SqliteManager as DestinyWeaponManager<Sqlite>>::get(&db.pool, "HERITAGE")
+        .await
+        .unwrap();
+    assert_eq!(weapon.id, 100);
+
+    let variants = Variant::resolve::<Sqlite, SqliteManager>(&db.pool, "heritage")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(variant, w)| (variant, w.id))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variants,
+        vec![
+            (Variant::Base, 100),
+            (Variant::Duplicate, 102),
+            (Variant::Adept, 101)
+        ]
+    );
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn resolves_variants() {
+    let db = Database::new("variants").await;
+
+    let variants = Variant::resolve::<Sqlite, SqliteManager>(&db.pool, "Heritage")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(variant, w)| (variant, w.id))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        variants,
+        vec![
+            (Variant::Base, 100),
+            (Variant::Duplicate, 102),
+            (Variant::Adept, 101)
+        ]
+    );
+
+    db.close().await;
+}
+
+#[tokio::test]
+async fn gets_perks_ignoring_case() {
+    let db = Database::new("perks").await;
+
+    let perk = <SqliteManager as DestinyPerkManager<Sqlite>>::get(&db.pool, "threat detector")
+        .await
+        .unwrap();
+    assert_eq!(perk.id, 2);
+
+    let names = [String::from("SLIDESHOT"), String::from("Threat Detector")];
+    let mut ids = SqliteManager::get_all(&db.pool, &names)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, vec![1, 2, 11]);
+
+    db.close().await;
+}
@@ -0,0 +1,252 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use endgame_analysis::endgame_analysis::{Affinity, Snapshot, Strictness, TierLabel, Variant};
+use endgame_analysis::wishlist::{
+    DimFormat, Wishlist, WishlistCache, WishlistDocument, WishlistEntry, WishlistOptions,
+    WishlistSerializer,
+};
+
+#[test]
+fn strictness_sets_minimum_tier() {
+    assert_eq!(Strictness::Soft.min_tier(), TierLabel::F);
+    assert_eq!(Strictness::Strict.min_tier(), TierLabel::B);
+    assert_eq!(Strictness::UberStrict.min_tier(), TierLabel::S);
+
+    assert!(TierLabel::S.is_at_least(TierLabel::B));
+    assert!(TierLabel::B.is_at_least(TierLabel::B));
+    assert!(!TierLabel::C.is_at_least(TierLabel::B));
+    assert!(!TierLabel::None.is_at_least(TierLabel::F));
+}
+
+#[tokio::test]
+async fn strictness_filters_weapons() {
+    let snapshot = common::snapshot().await;
+
+    let items = |strictness| {
+        Wishlist::generate(&snapshot, &WishlistOptions::from_strictness(strictness))
+            .entries
+            .iter()
+            .filter_map(|entry| entry.item_hash)
+            .collect::<BTreeSet<_>>()
+    };
+
+    assert_eq!(items(Strictness::Soft), BTreeSet::from([100, 101, 200]));
+    assert_eq!(items(Strictness::Strict), BTreeSet::from([100, 101, 200]));
+    assert_eq!(items(Strictness::VeryStrict), BTreeSet::from([100, 101]));
+    assert_eq!(items(Strictness::UberStrict), BTreeSet::from([100, 101]));
+}
+
+#[tokio::test]
+async fn estimate_matches_generated_lines() {
+    let snapshot = common::snapshot().await;
+    let options = WishlistOptions::from_strictness(Strictness::Soft);
+
+    assert_eq!(Wishlist::estimate(&snapshot, &options), 12);
+    assert_eq!(Wishlist::generate(&snapshot, &options).entries.len(), 12);
+}
+
+#[tokio::test]
+async fn dim_wishlist_matches_golden() {
+    let snapshot = common::snapshot().await;
+    let wishlist = Wishlist::generate_at(
+        &snapshot,
+        &WishlistOptions::from_strictness(Strictness::Soft),
+        snapshot.updated,
+    );
+
+    let rendered = DimFormat.serialize(&wishlist);
+
+    let golden = common::fixture(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/wishlist_soft.txt"
+    ));
+
+    assert_eq!(
+        rendered.lines().collect::<Vec<_>>(),
+        golden.lines().collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn writes_descriptive_notes() {
+    let snapshot = common::snapshot().await;
+
+    assert_eq!(
+        snapshot.weapons[0].notes("2025-01-01"),
+        "Tier S (#1) Kinetic Slug Shotgun. Perks: Slideshot, Threat Detector / Recombination, \
+         Trench Barrel. Origin: Nadir Focus. Endgame Analysis 2025-01-01 |tags:pve,tier-s"
+    );
+    assert_eq!(
+        snapshot.weapons[1].notes("2025-01-01"),
+        "Tier B (#2) Solar Aggressive Shotgun. Perks: Field Prep / One-Two Punch, Trench \
+         Barrel. Origin: None. Endgame Analysis 2025-01-01 |tags:pve,tier-b"
+    );
+}
+
+#[tokio::test]
+async fn caches_wishlists_per_snapshot() {
+    let mut snapshot = common::snapshot().await;
+    let options = WishlistOptions::from_strictness(Strictness::Soft);
+
+    let cache = WishlistCache::default();
+    let cached = cache.generate(&snapshot, &options);
+    assert!(Arc::ptr_eq(&cached, &cache.generate(&snapshot, &options)));
+
+    // A refresh within the same second keeps the version but not the weapons.
+    snapshot.weapons.pop();
+    let version = snapshot.version();
+    let refreshed = WishlistCache::default().generate(&snapshot, &options);
+    assert_eq!(snapshot.version(), version);
+    assert!(refreshed.entries.len() < cached.entries.len());
+}
+
+#[tokio::test]
+async fn keeps_weapons_when_trash_overlaps() {
+    let snapshot = common::snapshot().await;
+
+    let overlapping = WishlistOptions::from_strictness(Strictness::Soft).trash(Some(TierLabel::D));
+    let wishlist = Wishlist::generate(&snapshot, &overlapping);
+    assert!(wishlist.entries.iter().all(|entry| !entry.trash));
+    assert!(!wishlist.description.unwrap().contains("trash"));
+
+    let options = WishlistOptions::new(TierLabel::A).trash(Some(TierLabel::B));
+    let trash = Wishlist::generate(&snapshot, &options)
+        .entries
+        .into_iter()
+        .filter(|entry| entry.trash)
+        .filter_map(|entry| entry.item_hash)
+        .collect::<Vec<_>>();
+    assert_eq!(trash, vec![200]);
+}
+
+#[tokio::test]
+async fn trashes_rolls_on_mid_tier_weapons_only() {
+    let mut snapshot = common::snapshot().await;
+    let options = WishlistOptions::from_strictness(Strictness::Soft).trash_rolls(true);
+
+    let trash_rolls = |snapshot: &Snapshot| {
+        Wishlist::generate(snapshot, &options)
+            .entries
+            .into_iter()
+            .filter(|entry| entry.trash)
+            .map(|entry| (entry.item_hash.unwrap(), entry.perks))
+            .collect::<Vec<_>>()
+    };
+
+    // Heritage is S tier, so its unrecommended rolls are kept.
+    assert!(trash_rolls(&snapshot).is_empty());
+
+    snapshot.weapons[0].tier.tier = TierLabel::C;
+    assert_eq!(
+        trash_rolls(&snapshot),
+        vec![(100, vec![3, 6]), (101, vec![3, 6])]
+    );
+    assert_eq!(
+        Wishlist::estimate(&snapshot, &options),
+        Wishlist::generate(&snapshot, &options).entries.len()
+    );
+}
+
+#[test]
+fn parses_dim_wishlists_like_dim() {
+    let wishlist = "title: Rolls
+description: Some rolls
+// header comment
+this line is not part of the format
+
+// Heritage
+//notes:Block notes
+dimwishlist:item=100&perks=1,5&unknown=1
+dimwishlist:item=-200#notes:Inline notes
+some other tool's line
+dimwishlist:item=-69420&perks=7
+"
+    .parse::<WishlistDocument>()
+    .unwrap();
+
+    assert_eq!(wishlist.title.as_deref(), Some("Rolls"));
+    assert_eq!(wishlist.header, vec!["header comment"]);
+
+    let entry = |item_hash, perks: Vec<u32>, trash, notes: &str| WishlistEntry {
+        item_hash,
+        perks,
+        trash,
+        comment: Some(String::from("Heritage")),
+        notes: Some(String::from(notes)),
+    };
+    assert_eq!(
+        wishlist.entries,
+        vec![
+            entry(Some(100), vec![1, 5], false, "Block notes"),
+            entry(Some(200), Vec::new(), true, "Inline notes"),
+            entry(None, vec![7], false, "Block notes"),
+        ]
+    );
+
+    assert!("dimwishlist:perks=1".parse::<WishlistDocument>().is_err());
+}
+
+#[test]
+fn only_reads_header_comments_after_the_title() {
+    let wishlist = "// Heritage\ndimwishlist:item=100"
+        .parse::<WishlistDocument>()
+        .unwrap();
+    assert!(wishlist.header.is_empty());
+    assert_eq!(wishlist.entries[0].comment.as_deref(), Some("Heritage"));
+}
+
+#[tokio::test]
+async fn round_trips_dim_wishlists() {
+    let snapshot = common::snapshot().await;
+    let wishlist = Wishlist::generate(
+        &snapshot,
+        &WishlistOptions::from_strictness(Strictness::Soft),
+    );
+
+    let parsed = DimFormat
+        .serialize(&wishlist)
+        .parse::<WishlistDocument>()
+        .unwrap();
+    assert_eq!(parsed, wishlist);
+}
+
+#[test]
+fn names_files_without_separators() {
+    let options = WishlistOptions::from_strictness(Strictness::SemiStrict)
+        .affinities(vec![Affinity::Arc, Affinity::Strand])
+        .exclude_variants(vec![Variant::Adept, Variant::Timelost]);
+
+    assert_eq!(
+        options.label(),
+        "Semi-strict, Arc/Strand, no Adept/Timelost"
+    );
+    assert_eq!(
+        options.file_name(),
+        "pve-wishlist-semi-strict-arc-strand-no-adept-timelost"
+    );
+}
+
+#[tokio::test]
+async fn keeps_the_generation_time_of_unchanged_wishlists() {
+    let snapshot = common::snapshot().await;
+    let options = WishlistOptions::from_strictness(Strictness::Soft);
+    let earlier = snapshot.updated;
+    let later = earlier + chrono::Duration::days(1);
+
+    let previous = Wishlist::generate_at(&snapshot, &options, earlier);
+
+    let mut unchanged = Wishlist::generate_at(&snapshot, &options, later);
+    unchanged.keep_generated(&previous);
+    assert_eq!(unchanged, previous);
+
+    let mut changed = Wishlist::generate_at(
+        &snapshot,
+        &WishlistOptions::from_strictness(Strictness::Strict),
+        later,
+    );
+    changed.keep_generated(&previous);
+    assert_eq!(changed.header[0], "generated: 2025-01-02T00:00:00Z");
+}